@@ -0,0 +1,3 @@
+DELETE FROM submission
+WHERE progress->>'state' IN ('done', 'failed')
+    AND updated < CURRENT_TIMESTAMP - make_interval(mins => $1::int)
\ No newline at end of file
@@ -0,0 +1,5 @@
+DELETE FROM img
+WHERE id = $1
+    AND NOT EXISTS (SELECT 1 FROM feed WHERE feed.img_id = img.id)
+    AND NOT EXISTS (SELECT 1 FROM episode WHERE episode.img_id = img.id)
+RETURNING filename
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM webhook WHERE id = $1
\ No newline at end of file
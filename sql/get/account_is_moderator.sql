@@ -0,0 +1 @@
+SELECT id FROM Account WHERE id = $1 AND account_type = 'admin'
\ No newline at end of file
@@ -1,7 +1,10 @@
-Select id, name
+Select id, name, name as cursor_key, id::int8 as cursor_id
 FROM author
 WHERE id = ANY 
     (   SELECT author_id 
         FROM feed 
         WHERE status = 'online'
     )
+    AND ($1::text IS NULL OR (name, id) > ($1::text, $2::int8))
+ORDER BY name, id
+LIMIT $3
\ No newline at end of file
@@ -0,0 +1,10 @@
+SELECT fc.feed_id, c.id, c.description,
+       COALESCE(json_agg(json_build_object('id', sub.id, 'description', sub.description) ORDER BY sub.description)
+           FILTER (WHERE sub.id IS NOT NULL), '[]') as subcategories
+FROM feed_category fc
+    JOIN category c ON c.id = fc.category_id AND c.parent_id IS NULL
+    LEFT JOIN feed_category sfc ON sfc.feed_id = fc.feed_id
+    LEFT JOIN category sub ON sub.id = sfc.category_id AND sub.parent_id = c.id
+WHERE fc.feed_id = ANY($1)
+GROUP BY fc.feed_id, c.id, c.description
+ORDER BY fc.feed_id, c.description
\ No newline at end of file
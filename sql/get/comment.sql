@@ -3,9 +3,13 @@ SELECT comment.id,
        a.username, 
        a.id as user_id, 
        feed_id,
-       comment.created  
+       comment.created,
+       comment.created::text as cursor_key,
+       comment.id::int8 as cursor_id
 FROM comment 
     JOIN account a on a.id = comment.user_id 
     JOIN feed f on comment.feed_id = f.id
 WHERE feed_id = $1
-ORDER BY comment.created DESC
\ No newline at end of file
+    AND ($2::text IS NULL OR (comment.created, comment.id) < ($2::text::timestamptz, $3::int8))
+ORDER BY comment.created DESC, comment.id DESC
+LIMIT $4
\ No newline at end of file
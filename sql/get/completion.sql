@@ -1,7 +1,73 @@
-SELECT  f.title, author.name as author_name
-FROM
-    feed f LEFT JOIN author ON author.id = f.author_id
-WHERE
-    f.status = 'online' AND
-    f.search || author.search @@ to_tsquery(websearch_to_tsquery($1)::text || ':*')
-LIMIT 10
\ No newline at end of file
+-- $2 is $1 with the LIKE wildcards escaped.
+-- Input without terms, like punctuation only, gives no prefix query instead of a syntax error.
+WITH prefix AS (
+    SELECT CASE WHEN numnode(w) > 0 THEN to_tsquery('simple', w::text || ':*') END as query
+    FROM websearch_to_tsquery('simple', $1) w
+)
+(
+    SELECT  f.id::int8 as id, 'feed' as kind, f.title,
+            author.name as subtitle, img.filename as img,
+            (SELECT count(*) FROM subscription s WHERE s.feed_id = f.id)::int8 as popularity
+    FROM
+        feed f JOIN author ON author.id = f.author_id
+               LEFT JOIN img ON f.img_id = img.id
+    WHERE
+        f.status = 'online' AND
+        f.id IN (
+            SELECT feed.id
+            FROM feed JOIN search_queries($1) q ON q.config = feed.ts_config
+            WHERE feed.search @@ q.query
+            UNION
+            SELECT feed.id
+            FROM feed JOIN author a ON a.id = feed.author_id
+            WHERE a.search @@ (SELECT query FROM prefix)
+        )
+    ORDER BY popularity DESC, f.title
+    LIMIT 5
+)
+UNION ALL
+(
+    SELECT  author.id::int8 as id, 'author' as kind, author.name as title,
+            NULL as subtitle, NULL as img,
+            count(s.feed_id)::int8 as popularity
+    FROM
+        author JOIN feed f ON f.author_id = author.id
+               LEFT JOIN subscription s ON s.feed_id = f.id
+    WHERE
+        f.status = 'online' AND
+        author.search @@ (SELECT query FROM prefix)
+    GROUP BY author.id, author.name
+    ORDER BY popularity DESC, author.name
+    LIMIT 3
+)
+UNION ALL
+(
+    SELECT  c.id::int8 as id, 'category' as kind, c.description as title,
+            NULL as subtitle, NULL as img,
+            count(f.id)::int8 as popularity
+    FROM
+        category c JOIN feed_category fc ON fc.category_id = c.id
+                   JOIN feed f ON f.id = fc.feed_id
+    WHERE
+        f.status = 'online' AND
+        (c.description ILIKE $2 || '%' OR c.description ILIKE '% ' || $2 || '%')
+    GROUP BY c.id, c.description
+    ORDER BY popularity DESC, c.description
+    LIMIT 3
+)
+UNION ALL
+(
+    SELECT  e.id as id, 'episode' as kind, e.title,
+            f.title as subtitle, COALESCE(ei.filename, fi.filename) as img,
+            (SELECT count(*) FROM subscription s WHERE s.feed_id = f.id)::int8 as popularity
+    FROM
+        episode e JOIN feed f ON f.id = e.feed_id
+                  JOIN search_queries($1) q ON q.config = f.ts_config
+                  LEFT JOIN img ei ON e.img_id = ei.id
+                  LEFT JOIN img fi ON f.img_id = fi.id
+    WHERE
+        f.status = 'online' AND
+        e.search @@ q.query
+    ORDER BY popularity DESC, e.published DESC NULLS LAST
+    LIMIT 3
+)
\ No newline at end of file
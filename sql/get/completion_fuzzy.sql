@@ -0,0 +1,24 @@
+(
+    SELECT  f.id::int8 as id, 'feed' as kind, f.title,
+            author.name as subtitle, img.filename as img,
+            greatest(similarity(f.title, $1), word_similarity($1, f.title)) as score
+    FROM
+        feed f JOIN author ON author.id = f.author_id
+               LEFT JOIN img ON f.img_id = img.id
+    WHERE
+        f.status = 'online' AND (f.title % $1 OR $1 <% f.title)
+    ORDER BY score DESC
+    LIMIT 5
+)
+UNION ALL
+(
+    SELECT  author.id::int8 as id, 'author' as kind, author.name as title,
+            NULL as subtitle, NULL as img,
+            similarity(author.name, $1) as score
+    FROM author
+    WHERE
+        author.name % $1 AND
+        EXISTS (SELECT 1 FROM feed f WHERE f.author_id = author.id AND f.status = 'online')
+    ORDER BY score DESC
+    LIMIT 3
+)
\ No newline at end of file
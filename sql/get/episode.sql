@@ -1,16 +1,21 @@
 SELECT
-    id,
-    title,
-    description,
-    published,
-    explicit,
-    duration,
-    show_notes,
-    url as web_link,
-    media_url,
-    keywords,
-    media_length,
-    mime_type,
-    guid
-FROM episode 
-WHERE id = $1
\ No newline at end of file
+    e.id,
+    e.title,
+    e.description,
+    e.published,
+    e.explicit,
+    e.duration,
+    e.show_notes,
+    e.url as web_link,
+    e.media_url,
+    e.keywords,
+    e.media_length,
+    e.mime_type,
+    e.guid,
+    COALESCE(ei.link, fi.link) as img,
+    COALESCE(ei.filename, fi.filename) as img_cache
+FROM episode e
+    JOIN feed f ON f.id = e.feed_id
+    LEFT JOIN img ei ON e.img_id = ei.id
+    LEFT JOIN img fi ON f.img_id = fi.id
+WHERE e.id = $1
\ No newline at end of file
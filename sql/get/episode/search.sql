@@ -0,0 +1,42 @@
+SELECT  e.id,
+        e.title,
+        e.description,
+        e.published,
+        e.explicit,
+        e.duration,
+        e.show_notes,
+        e.url as web_link,
+        e.media_url,
+        e.keywords,
+        e.media_length,
+        e.mime_type,
+        e.guid,
+        COALESCE(ei.link, fi.link) as img,
+        COALESCE(ei.filename, fi.filename) as img_cache,
+        f.id as feed_id,
+        f.title as feed_title,
+        fi.filename as feed_img,
+        ts_headline(f.ts_config, e.title, q.query,
+            $6::text || ', HighlightAll=true') as hl_title,
+        ts_headline(f.ts_config, e.description, q.query,
+            $6::text || ', MaxFragments=2') as hl_description,
+        ts_rank(e.search, q.query)::text as cursor_key,
+        e.id as cursor_id
+FROM episode e
+    JOIN feed f ON f.id = e.feed_id
+    JOIN search_queries($1) q ON q.config = f.ts_config
+    LEFT JOIN feed_language ON feed_language.id = f.language
+    LEFT JOIN img ei ON e.img_id = ei.id
+    LEFT JOIN img fi ON f.img_id = fi.id
+WHERE
+        f.status = 'online' AND
+        e.search @@ q.query
+        AND ($2::int IS NULL OR f.id = $2)
+        AND ($3::text IS NULL OR feed_language.name = $3)
+        AND ($4::timestamptz IS NULL OR e.published >= $4)
+        AND ($5::timestamptz IS NULL OR e.published <= $5)
+        AND ($7::text IS NULL OR (ts_rank(e.search, q.query), e.id) < ($7::text::real, $8::int8))
+ORDER BY
+    ts_rank(e.search, q.query) DESC,
+    e.id DESC
+LIMIT $9
\ No newline at end of file
@@ -11,8 +11,17 @@ SELECT
     e.keywords,
     e.media_length,
     e.mime_type,
-    e.guid
+    e.guid,
+    COALESCE(ei.link, fi.link) as img,
+    COALESCE(ei.filename, fi.filename) as img_cache,
+    COALESCE(e.published, '-infinity')::text as cursor_key,
+    e.id as cursor_id
 FROM episode e
-WHERE e.feed_id = $1 AND e.id > $2
-ORDER BY e.published DESC
-LIMIT $3
\ No newline at end of file
+    JOIN feed f ON f.id = e.feed_id
+    LEFT JOIN img ei ON e.img_id = ei.id
+    LEFT JOIN img fi ON f.img_id = fi.id
+WHERE e.feed_id = $1
+    AND ($2::text IS NULL OR
+        (COALESCE(e.published, '-infinity'), e.id) < ($2::text::timestamptz, $3::int8))
+ORDER BY COALESCE(e.published, '-infinity') DESC, e.id DESC
+LIMIT $4
\ No newline at end of file
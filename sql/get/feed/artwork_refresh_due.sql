@@ -0,0 +1,13 @@
+-- online feeds without a waiting artwork refresh or one finished within $1 seconds
+SELECT f.id
+FROM feed f
+WHERE f.status = 'online'
+    AND NOT EXISTS (
+        SELECT 1
+        FROM job j
+        WHERE j.kind = 'refresh_artwork'
+            AND j.payload->>'feedId' = f.id::text
+            AND (j.status IN ('queued', 'running')
+                OR j.updated > CURRENT_TIMESTAMP - make_interval(secs => $1::float8))
+    )
+ORDER BY f.id
\ No newline at end of file
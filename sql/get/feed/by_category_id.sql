@@ -1,13 +1,18 @@
-SELECT  distinct f.id, f.title,
+SELECT  f.id, f.title,
         f.description, f.subtitle,
         f.url, img.link as img,
         f.link_web, author.name as author_name,
         feed_language.name as language, f.submitted,
-        img.filename as img_cache
+        img.filename as img_cache,
+        img.blurhash as img_blurhash, img.color as img_color,
+        f.title as cursor_key, f.id::int8 as cursor_id
 FROM
-    feed f LEFT JOIN  author ON author.id = f.author_id
+    feed f LEFT JOIN author ON author.id = f.author_id
            LEFT JOIN feed_language ON feed_language.id = f.language
            LEFT JOIN img ON f.img_id = img.id
-           LEFT JOIN feed_category  on f.id = feed_category.feed_id
 WHERE
-    f.status = 'online' AND feed_category.category_id = $1
\ No newline at end of file
+    f.status = 'online' AND
+    EXISTS (SELECT 1 FROM feed_category fc WHERE fc.feed_id = f.id AND fc.category_id = $1)
+    AND ($2::text IS NULL OR (f.title, f.id) > ($2::text, $3::int8))
+ORDER BY f.title, f.id
+LIMIT $4
\ No newline at end of file
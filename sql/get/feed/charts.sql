@@ -1,15 +1,25 @@
-SELECT  f.id,
-        f.title,
-        f.subtitle,
-        author.name as author_name,
-        f.submitted,
-        img.filename as img,
-        f.status
-FROM subscription s
-    RIGHT JOIN feed f on s.feed_id = f.id
-    JOIN author on f.author_id = author.id
-    JOIN img on f.img_id = img.id
-WHERE f.status = 'online'
-GROUP BY f.id, f.title, f.subtitle, author_name, img, f.status
-ORDER BY count(s.feed_id) DESC, (select count(feed_id) FROM comment where feed_id = comment.feed_id ) DESC
-LIMIT 20;
\ No newline at end of file
+SELECT *, subscriptions || ',' || comments as cursor_key, id::int8 as cursor_id
+FROM (
+    SELECT  f.id,
+            f.title,
+            f.subtitle,
+            author.name as author_name,
+            f.submitted,
+            img.filename as img,
+            img.blurhash as img_blurhash,
+            img.color as img_color,
+            f.status,
+            count(s.feed_id) as subscriptions,
+            (SELECT count(*) FROM comment c WHERE c.feed_id = f.id) as comments
+    FROM subscription s
+        RIGHT JOIN feed f on s.feed_id = f.id
+        JOIN author on f.author_id = author.id
+        JOIN img on f.img_id = img.id
+    WHERE f.status = 'online'
+    GROUP BY f.id, f.title, f.subtitle, author_name, img, img_blurhash, img_color, f.status
+) charts
+-- the cursor key holds subscriptions and comments, separated by a comma
+WHERE $1::text IS NULL
+    OR (subscriptions, comments, id) < (split_part($1, ',', 1)::int8, split_part($1, ',', 2)::int8, $2::int8)
+ORDER BY subscriptions DESC, comments DESC, id DESC
+LIMIT $3
\ No newline at end of file
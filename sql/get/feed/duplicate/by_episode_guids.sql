@@ -0,0 +1,11 @@
+-- the saved feed sharing the most GUIDs and how many GUIDs it has itself
+SELECT best.feed_id, best.overlap,
+    (SELECT count(DISTINCT guid) FROM episode e WHERE e.feed_id = best.feed_id)::int8 as episodes
+FROM (
+    SELECT feed_id, count(DISTINCT guid)::int8 as overlap
+    FROM episode
+    WHERE guid = ANY($1)
+    GROUP BY feed_id
+    ORDER BY overlap DESC, feed_id
+    LIMIT 1
+) best
\ No newline at end of file
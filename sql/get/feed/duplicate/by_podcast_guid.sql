@@ -0,0 +1 @@
+SELECT id as feed_id FROM feed WHERE podcast_guid = $1 ORDER BY id LIMIT 1
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT feed_id FROM feed_url WHERE url = ANY($1) ORDER BY feed_id LIMIT 1
\ No newline at end of file
@@ -1,3 +0,0 @@
-SELECT id 
-FROM feed 
-WHERE feed.title = $1 or url = $2
\ No newline at end of file
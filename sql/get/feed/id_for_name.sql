@@ -1,3 +1,2 @@
-SELECT id FROM feed WHERE title ILIKE $1
-
--- lowercase should workd
\ No newline at end of file
+-- titles are not unique, the feed saved first answers for its title
+SELECT id FROM feed WHERE title ILIKE $1 ORDER BY id LIMIT 1
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT f.url, f.img_id, img.link as img
+FROM feed f LEFT JOIN img ON f.img_id = img.id
+WHERE f.id = $1
\ No newline at end of file
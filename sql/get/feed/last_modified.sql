@@ -0,0 +1,5 @@
+SELECT greatest(f.last_modified, max(e.published)) as last_modified
+FROM feed f
+    LEFT JOIN episode e ON e.feed_id = f.id AND e.published <= CURRENT_TIMESTAMP
+WHERE f.id = $1 AND f.status = 'online'
+GROUP BY f.id
\ No newline at end of file
@@ -4,7 +4,8 @@ SELECT  f.id, f.title,
         f.link_web, author.name as author_name,
         feed_language.name as language,
         f.submitted,
-        img.filename as img_cache
+        img.filename as img_cache,
+        img.blurhash as img_blurhash, img.color as img_color
 FROM
     feed f
         JOIN  author ON author.id = f.author_id
@@ -4,11 +4,16 @@ SELECT  f.id,
         author.name as author_name,
         f.submitted,
         img.filename as img,
-        f.status
+        img.blurhash as img_blurhash,
+        img.color as img_color,
+        f.status,
+        r.modified::text as cursor_key,
+        f.id::int8 as cursor_id
 FROM
     review r JOIN feed f ON r.feed_id = f.id
              JOIN  author ON author.id = f.author_id
              JOIN img on f.img_id = img.id
 WHERE f.status = 'online' AND r.status = 'done'
-ORDER BY r.modified DESC, f.submitted DESC
-LIMIT 20
\ No newline at end of file
+    AND ($1::text IS NULL OR (r.modified, f.id) < ($1::text::timestamptz, $2::int8))
+ORDER BY r.modified DESC, f.id DESC
+LIMIT $3
\ No newline at end of file
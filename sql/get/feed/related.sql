@@ -3,6 +3,8 @@ SELECT  f.id,
         f.description,
         f.submitted,
         img.filename as img,
+        img.blurhash as img_blurhash,
+        img.color as img_color,
         author.name as author_name,
         f.status,
         f.subtitle,
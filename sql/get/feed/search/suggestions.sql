@@ -0,0 +1,16 @@
+SELECT suggestion
+FROM (
+    SELECT f.title as suggestion,
+           greatest(similarity(f.title, $1), word_similarity($1, f.title)) as score
+    FROM feed f
+    WHERE f.status = 'online' AND (f.title % $1 OR $1 <% f.title)
+
+    UNION
+
+    SELECT author.name as suggestion,
+           greatest(similarity(author.name, $1), word_similarity($1, author.name)) as score
+    FROM author JOIN feed f ON f.author_id = author.id
+    WHERE f.status = 'online' AND (author.name % $1 OR $1 <% author.name)
+) suggestions
+ORDER BY score DESC, suggestion
+LIMIT 5
\ No newline at end of file
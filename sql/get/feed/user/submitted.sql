@@ -1,12 +1,19 @@
 SELECT  f.id,
-        f.title, 
+        f.title,
         f.description,
-        f.submitted, 
-        img.filename as img, 
+        f.submitted,
+        img.filename as img,
+        img.blurhash as img_blurhash,
+        img.color as img_color,
         author.name as author_name,
         f.status,
-        f.subtitle
+        f.subtitle,
+        f.submitted::text as cursor_key,
+        f.id::int8 as cursor_id
 FROM feed f JOIN author ON author.id = f.author_id
             JOIN img ON f.img_id = img.id
 WHERE f.submitter_id = $1
-ORDER BY f.status, f.submitted
\ No newline at end of file
+    AND ($2::feed_status IS NULL OR f.status = $2)
+    AND ($3::text IS NULL OR (f.submitted, f.id) < ($3::text::timestamptz, $4::int8))
+ORDER BY f.submitted DESC, f.id DESC
+LIMIT $5
\ No newline at end of file
@@ -3,12 +3,17 @@ SELECT f.id,
        f.subtitle,
        author.name as author_name,
        img.filename as img,
+       img.blurhash as img_blurhash,
+       img.color as img_color,
        f.status,
-       s.created as submitted
+       s.created as submitted,
+       f.title as cursor_key,
+       f.id::int8 as cursor_id
 FROM feed f
-       LEFT JOIN subscription s ON s.feed_id = f.id
+       JOIN subscription s ON s.feed_id = f.id
        LEFT JOIN author ON author.id = f.author_id
-       LEFT JOIN feed_language ON feed_language.id = f.language
        LEFT JOIN img ON f.img_id = img.id
-WHERE f.status = 'online' AND s.user_id = $1 
-ORDER BY f.title
\ No newline at end of file
+WHERE f.status = 'online' AND s.user_id = $1
+       AND ($2::text IS NULL OR (f.title, f.id) > ($2::text, $3::int8))
+ORDER BY f.title, f.id
+LIMIT $4
\ No newline at end of file
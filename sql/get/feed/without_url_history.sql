@@ -0,0 +1,3 @@
+SELECT id, url
+FROM feed
+WHERE NOT EXISTS (SELECT 1 FROM feed_url WHERE feed_url.feed_id = feed.id)
\ No newline at end of file
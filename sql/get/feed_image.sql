@@ -1,4 +1,4 @@
-SELECT img.filename as img, f.id as feed_id
+SELECT img.filename as img, img.blurhash, img.color, f.id as feed_id
 FROM feed f JOIN img ON f.img_id = img.id
 WHERE f.title = $1 AND f.status = 'online'
 LIMIT 1
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT filename
+FROM img
+WHERE id = $1 AND blurhash IS NULL
\ No newline at end of file
@@ -0,0 +1,9 @@
+SELECT id, kind, payload, status, attempts, max_attempts,
+       run_at, last_error, created, updated,
+       updated::text as cursor_key,
+       id::int8 as cursor_id
+FROM job
+WHERE ($1::job_status IS NULL OR status = $1)
+    AND ($2::text IS NULL OR (updated, id) < ($2::text::timestamptz, $3::int8))
+ORDER BY updated DESC, id DESC
+LIMIT $4
\ No newline at end of file
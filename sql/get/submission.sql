@@ -0,0 +1,4 @@
+SELECT s.id, s.account_id, a.username, s.feed_url, s.progress, s.feed_id, s.updated
+FROM submission s
+    JOIN account a ON a.id = s.account_id
+WHERE s.id = $1
\ No newline at end of file
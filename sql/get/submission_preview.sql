@@ -0,0 +1,3 @@
+SELECT preview
+FROM submission
+WHERE id = $1
\ No newline at end of file
@@ -0,0 +1,8 @@
+SELECT tag as name, count(*)::int8 as count
+FROM episode e
+    JOIN feed f ON f.id = e.feed_id,
+    unnest(e.keywords) tag
+WHERE f.status = 'online'
+GROUP BY tag
+ORDER BY count DESC, tag
+LIMIT $1
\ No newline at end of file
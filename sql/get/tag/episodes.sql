@@ -0,0 +1,30 @@
+SELECT  e.id,
+        e.title,
+        e.description,
+        e.published,
+        e.explicit,
+        e.duration,
+        e.show_notes,
+        e.url as web_link,
+        e.media_url,
+        e.keywords,
+        e.media_length,
+        e.mime_type,
+        e.guid,
+        COALESCE(ei.link, fi.link) as img,
+        COALESCE(ei.filename, fi.filename) as img_cache,
+        f.id as feed_id,
+        f.title as feed_title,
+        fi.filename as feed_img,
+        COALESCE(e.published, '-infinity')::text as cursor_key,
+        e.id as cursor_id
+FROM episode e
+    JOIN feed f ON f.id = e.feed_id
+    LEFT JOIN img ei ON e.img_id = ei.id
+    LEFT JOIN img fi ON f.img_id = fi.id
+WHERE
+    f.status = 'online' AND e.keywords @> ARRAY[$1::text]
+    AND ($2::text IS NULL OR
+        (COALESCE(e.published, '-infinity'), e.id) < ($2::text::timestamptz, $3::int8))
+ORDER BY COALESCE(e.published, '-infinity') DESC, e.id DESC
+LIMIT $4
\ No newline at end of file
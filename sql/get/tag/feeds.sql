@@ -0,0 +1,20 @@
+SELECT *, tagged::text as cursor_key, id::int8 as cursor_id
+FROM (
+    SELECT  f.id, f.title,
+            f.description, f.subtitle,
+            f.url, img.link as img,
+            f.link_web, author.name as author_name,
+            feed_language.name as language, f.submitted,
+            img.filename as img_cache,
+            img.blurhash as img_blurhash, img.color as img_color,
+            (SELECT count(*) FROM episode e WHERE e.feed_id = f.id AND e.keywords @> ARRAY[$1::text]) as tagged
+    FROM
+        feed f JOIN author ON author.id = f.author_id
+               JOIN feed_language ON feed_language.id = f.language
+               JOIN img ON f.img_id = img.id
+    WHERE f.status = 'online'
+) tagged_feeds
+WHERE tagged > 0
+    AND ($2::text IS NULL OR (tagged, id) < ($2::text::int8, $3::int8))
+ORDER BY tagged DESC, id DESC
+LIMIT $4
\ No newline at end of file
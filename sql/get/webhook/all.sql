@@ -0,0 +1,3 @@
+SELECT id, url, events, active, created
+FROM webhook
+ORDER BY id
\ No newline at end of file
@@ -0,0 +1,9 @@
+SELECT id, webhook_id, event, payload, status, attempts,
+       response_status, last_error, created, delivered,
+       created::text as cursor_key,
+       id::int8 as cursor_id
+FROM webhook_delivery
+WHERE webhook_id = $1
+    AND ($2::text IS NULL OR (created, id) < ($2::text::timestamptz, $3::int8))
+ORDER BY created DESC, id DESC
+LIMIT $4
\ No newline at end of file
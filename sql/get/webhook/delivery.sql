@@ -0,0 +1,3 @@
+SELECT d.id, d.event, d.payload, d.created, w.url, w.secret
+FROM webhook_delivery d JOIN webhook w ON w.id = d.webhook_id
+WHERE d.id = $1
\ No newline at end of file
@@ -0,0 +1 @@
+INSERT INTO Account(username, password_hash, email, account_type) VALUES($1, $2, $3, $4)
\ No newline at end of file
@@ -0,0 +1,16 @@
+WITH input as (
+    SELECT * FROM unnest($1::text[], $2::int4[]) AS i(description, parent_id)
+), inserted as (
+    INSERT INTO
+    category(description, parent_id)
+    SELECT DISTINCT ON (description) description, parent_id
+    FROM input
+    ON CONFLICT DO NOTHING
+    RETURNING id, description
+)
+SELECT id, description FROM inserted
+
+UNION ALL
+
+SELECT category.id, category.description
+FROM category JOIN input ON category.description = input.description
\ No newline at end of file
@@ -0,0 +1,17 @@
+INSERT INTO
+    episode(title, description, published, explicit, keywords,
+            duration, show_notes, url, media_url, feed_id, guid,
+            media_length, mime_type, img_id
+            )
+SELECT title, description, published, explicit,
+       CASE WHEN keywords IS NULL THEN NULL
+            ELSE ARRAY(SELECT jsonb_array_elements_text(keywords))
+       END,
+       duration, show_notes, url, media_url, $1, guid,
+       media_length, mime_type, img_id
+FROM unnest($2::text[], $3::text[], $4::timestamptz[], $5::bool[], $6::jsonb[],
+            $7::int8[], $8::text[], $9::text[], $10::text[], $11::text[],
+            $12::int8[], $13::text[], $14::int4[])
+    AS ep(title, description, published, explicit, keywords,
+          duration, show_notes, url, media_url, guid,
+          media_length, mime_type, img_id)
\ No newline at end of file
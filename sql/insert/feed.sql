@@ -7,6 +7,7 @@ INSERT INTO feed (
     subtitle,
     url,
     language,
-    link_web
+    link_web,
+    podcast_guid
 )
-VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id
\ No newline at end of file
+VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING id
\ No newline at end of file
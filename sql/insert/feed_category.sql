@@ -0,0 +1,2 @@
+INSERT INTO feed_category (feed_id, category_id)
+SELECT $1, unnest($2::int4[])
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO feed_url (feed_id, url)
+SELECT $1, unnest($2::text[])
+ON CONFLICT (feed_id, url) DO NOTHING
\ No newline at end of file
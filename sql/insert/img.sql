@@ -1,15 +1,10 @@
-WITH inserted as (
+WITH upserted as (
     INSERT INTO
-    img(link, hash, filename)
+    img(link, hash, filename, blurhash, color)
     VALUES
-        ($1, $2, $3)
-    ON CONFLICT DO NOTHING
-    RETURNING ID
+        ($1, $2, $3, $4, $5)
+    -- the same bytes under a new URL, the refresh compares against the latest link
+    ON CONFLICT (hash) DO UPDATE SET link = EXCLUDED.link
+    RETURNING id
 )
-SELECT id FROM inserted
-
-UNION
-
-SELECT id
-FROM img
-WHERE hash = $2
\ No newline at end of file
+SELECT id FROM upserted
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO job(kind, payload, run_at, max_attempts)
+VALUES($1, $2, $3, $4)
+RETURNING id
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO submission(account_id, feed_url, progress, preview)
+VALUES ($1, $2, $3::jsonb, $4)
+RETURNING id, updated
\ No newline at end of file
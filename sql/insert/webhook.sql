@@ -0,0 +1,3 @@
+INSERT INTO webhook(url, secret, events)
+VALUES($1, $2, $3)
+RETURNING id, url, events, active, created
\ No newline at end of file
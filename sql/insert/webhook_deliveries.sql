@@ -0,0 +1,6 @@
+-- a delivery for every active webhook subscribed to the event
+INSERT INTO webhook_delivery(webhook_id, event, payload)
+SELECT id, $1::text, $2::jsonb
+FROM webhook
+WHERE active AND $1::text = ANY(events)
+RETURNING id
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE episode ADD COLUMN img_id integer references img(id) on delete set null;
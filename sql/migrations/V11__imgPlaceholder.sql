@@ -0,0 +1,2 @@
+ALTER TABLE img ADD COLUMN blurhash text check ( blurhash <> '' );
+ALTER TABLE img ADD COLUMN color text check ( color ~ '^#[0-9a-f]{6}$' );
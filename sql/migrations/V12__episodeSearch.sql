@@ -0,0 +1,26 @@
+ALTER TABLE episode
+    add column search tsvector NOT NULL default '';
+
+Update episode
+    set search =
+        setweight(to_tsvector(coalesce(title,'')), 'A') ||
+        setweight(to_tsvector(coalesce(description,'')), 'B') ||
+        setweight(to_tsvector(coalesce(regexp_replace(show_notes, '<[^>]*>', ' ', 'g'),'')), 'C') ||
+        setweight(to_tsvector(coalesce(array_to_string(keywords, ' '),'')), 'D');
+
+
+CREATE FUNCTION episode_tsvector() RETURNS trigger AS $$
+begin
+    new.search :=
+        setweight(to_tsvector(coalesce(new.title,'')), 'A') ||
+        setweight(to_tsvector(coalesce(new.description,'')), 'B') ||
+        setweight(to_tsvector(coalesce(regexp_replace(new.show_notes, '<[^>]*>', ' ', 'g'),'')), 'C') ||
+        setweight(to_tsvector(coalesce(array_to_string(new.keywords, ' '),'')), 'D');
+    return new;
+end
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER episode_tsvector_update BEFORE INSERT OR UPDATE
+    ON episode FOR EACH ROW EXECUTE PROCEDURE episode_tsvector();
+
+CREATE INDEX episode_search_index ON episode USING GIN(search);
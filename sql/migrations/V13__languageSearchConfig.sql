@@ -0,0 +1,73 @@
+CREATE FUNCTION ts_config_for(language_code text) RETURNS regconfig AS $$
+    SELECT CASE lower(language_code)
+        WHEN 'ar' THEN 'arabic'
+        WHEN 'da' THEN 'danish'
+        WHEN 'de' THEN 'german'
+        WHEN 'el' THEN 'greek'
+        WHEN 'en' THEN 'english'
+        WHEN 'es' THEN 'spanish'
+        WHEN 'fi' THEN 'finnish'
+        WHEN 'fr' THEN 'french'
+        WHEN 'hu' THEN 'hungarian'
+        WHEN 'id' THEN 'indonesian'
+        WHEN 'it' THEN 'italian'
+        WHEN 'lt' THEN 'lithuanian'
+        WHEN 'ne' THEN 'nepali'
+        WHEN 'nl' THEN 'dutch'
+        WHEN 'no' THEN 'norwegian'
+        WHEN 'pt' THEN 'portuguese'
+        WHEN 'ro' THEN 'romanian'
+        WHEN 'ru' THEN 'russian'
+        WHEN 'sv' THEN 'swedish'
+        WHEN 'ta' THEN 'tamil'
+        WHEN 'tr' THEN 'turkish'
+        ELSE 'simple'
+    END::regconfig;
+$$ LANGUAGE sql IMMUTABLE;
+
+---feed
+ALTER TABLE feed
+    add column ts_config regconfig NOT NULL default 'simple';
+
+CREATE OR REPLACE FUNCTION feed_tsvector() RETURNS trigger AS $$
+begin
+    new.ts_config := ts_config_for((SELECT name FROM feed_language WHERE id = new.language));
+    new.search :=
+        setweight(to_tsvector(new.ts_config, coalesce(new.title,'')), 'A') ||
+        setweight(to_tsvector(new.ts_config, coalesce(new.description,'')), 'B') ||
+        setweight(to_tsvector(new.ts_config, coalesce(new.subtitle,'')), 'C');
+    return new;
+end
+$$ LANGUAGE plpgsql;
+
+UPDATE feed SET title = title;
+---feed
+
+---author
+-- names are not stemmed
+CREATE OR REPLACE FUNCTION author_tsvector() RETURNS trigger AS $$
+begin
+    new.search := setweight(to_tsvector('simple', coalesce(new.name,'')), 'A');
+    return new;
+end
+$$ LANGUAGE plpgsql;
+
+UPDATE author SET name = name;
+---author
+
+---episode
+CREATE OR REPLACE FUNCTION episode_tsvector() RETURNS trigger AS $$
+declare
+    config regconfig := coalesce((SELECT ts_config FROM feed WHERE id = new.feed_id), 'simple');
+begin
+    new.search :=
+        setweight(to_tsvector(config, coalesce(new.title,'')), 'A') ||
+        setweight(to_tsvector(config, coalesce(new.description,'')), 'B') ||
+        setweight(to_tsvector(config, coalesce(regexp_replace(new.show_notes, '<[^>]*>', ' ', 'g'),'')), 'C') ||
+        setweight(to_tsvector(config, coalesce(array_to_string(new.keywords, ' '),'')), 'D');
+    return new;
+end
+$$ LANGUAGE plpgsql;
+
+UPDATE episode SET title = title;
+---episode
@@ -0,0 +1,4 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX feed_title_trgm_index ON feed USING GIN(title gin_trgm_ops);
+CREATE INDEX author_name_trgm_index ON author USING GIN(name gin_trgm_ops);
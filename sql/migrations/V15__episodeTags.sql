@@ -0,0 +1,10 @@
+-- keywords were stored as they appeared in the feed, normalise them like new episodes
+UPDATE episode
+SET keywords = (
+    SELECT array_agg(DISTINCT k ORDER BY k)
+    FROM (SELECT lower(trim(unnest(keywords))) as k) t
+    WHERE k <> ''
+)
+WHERE keywords IS NOT NULL;
+
+CREATE INDEX episode_keywords_index ON episode USING GIN(keywords);
\ No newline at end of file
@@ -0,0 +1,17 @@
+create type job_status as enum ('queued', 'running', 'done', 'failed');
+
+CREATE TABLE job(
+    id bigserial primary key,
+    kind text not null check ( kind <> '' ),
+    payload jsonb not null default '{}',
+    status job_status not null default 'queued',
+    attempts integer not null default 0,
+    max_attempts integer not null default 5 check ( max_attempts > 0 ),
+    run_at timestamptz not null default CURRENT_TIMESTAMP,
+    last_error text,
+    created timestamptz not null default CURRENT_TIMESTAMP,
+    updated timestamptz not null default CURRENT_TIMESTAMP
+);
+
+-- the workers only ever look for due jobs
+CREATE INDEX job_due_index ON job(run_at) WHERE status IN ('queued', 'running');
\ No newline at end of file
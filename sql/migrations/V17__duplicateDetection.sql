@@ -0,0 +1,15 @@
+-- shows are told apart by URL, podcast:guid and episode GUIDs, two of them may share a title
+ALTER TABLE feed DROP CONSTRAINT IF EXISTS feed_title_key;
+
+ALTER TABLE feed ADD COLUMN podcast_guid text check ( podcast_guid <> '' );
+CREATE INDEX feed_podcast_guid_index ON feed(podcast_guid);
+
+-- every normalised URL a feed was known under, filled at startup for existing feeds
+CREATE TABLE feed_url(
+    feed_id integer references feed(id) on delete cascade not null,
+    url text not null check ( url <> '' ),
+    PRIMARY KEY (feed_id, url)
+);
+CREATE INDEX feed_url_url_index ON feed_url(url);
+
+CREATE INDEX episode_guid_index ON episode(guid);
\ No newline at end of file
@@ -0,0 +1,26 @@
+CREATE TABLE webhook(
+    id serial primary key,
+    url text not null check ( url <> '' ),
+    secret text not null check ( secret <> '' ),
+    events text[] not null,
+    active bool not null default true,
+    created timestamptz not null default CURRENT_TIMESTAMP
+);
+
+create type delivery_status as enum ('pending', 'delivered', 'failed');
+
+-- one row per event and webhook, updated on every attempt
+CREATE TABLE webhook_delivery(
+    id bigserial primary key,
+    webhook_id integer references webhook(id) on delete cascade not null,
+    event text not null,
+    payload jsonb not null,
+    status delivery_status not null default 'pending',
+    attempts integer not null default 0,
+    response_status integer,
+    last_error text,
+    created timestamptz not null default CURRENT_TIMESTAMP,
+    delivered timestamptz
+);
+
+CREATE INDEX webhook_delivery_webhook_index ON webhook_delivery(webhook_id, created);
\ No newline at end of file
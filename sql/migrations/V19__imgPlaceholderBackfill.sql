@@ -0,0 +1,6 @@
+-- images cached before V11 get their placeholder from the file already on disk,
+-- one queued job per image, see `queue::Task::ImagePlaceholder`
+INSERT INTO job(kind, payload)
+SELECT 'image_placeholder', jsonb_build_object('imgId', id)
+FROM img
+WHERE blurhash IS NULL;
\ No newline at end of file
@@ -0,0 +1,30 @@
+-- The prefix query of a search term for every text search configuration in use.
+-- Joined on `feed.ts_config` the right side of `@@` is one constant per configuration,
+-- so `search_index` and `episode_search_index` can answer the match.
+CREATE FUNCTION search_queries(term text) RETURNS TABLE(config regconfig, query tsquery) AS $$
+    SELECT c.config, to_tsquery(c.config, websearch_to_tsquery(c.config, term)::text || ':*')
+    FROM (
+        SELECT ts_config_for(name) AS config FROM feed_language
+        UNION
+        SELECT 'simple'::regconfig
+    ) c
+    WHERE websearch_to_tsquery(c.config, term)::text <> ''
+$$ LANGUAGE sql STABLE ROWS 8;
+
+-- exact title matches lost their index with feed_title_key
+CREATE INDEX feed_title_index ON feed(title);
+
+---episode
+-- episodes are indexed with the configuration of their feed, which follows its language
+CREATE FUNCTION feed_episodes_tsvector() RETURNS trigger AS $$
+begin
+    UPDATE episode SET title = title WHERE feed_id = new.id;
+    return null;
+end
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER feed_episodes_tsvector_update AFTER UPDATE OF language, ts_config
+    ON feed FOR EACH ROW
+    WHEN (old.ts_config IS DISTINCT FROM new.ts_config)
+    EXECUTE PROCEDURE feed_episodes_tsvector();
+---episode
@@ -0,0 +1,10 @@
+-- progress of the `submit_feed` jobs, read at `/user/job/{id}`
+CREATE TABLE submission(
+    id bigserial primary key,
+    account_id integer references account(id) on delete cascade not null,
+    feed_url text not null check ( feed_url <> '' ),
+    progress jsonb not null,
+    updated timestamptz not null default CURRENT_TIMESTAMP
+);
+
+CREATE INDEX submission_updated_index ON submission(updated);
@@ -0,0 +1,8 @@
+-- a URL belongs to one feed, so two concurrent submissions of a show cannot both be saved;
+-- URLs recorded twice stay with the feed saved first
+DELETE FROM feed_url a
+    USING feed_url b
+WHERE a.url = b.url AND a.feed_id > b.feed_id;
+
+DROP INDEX feed_url_url_index;
+ALTER TABLE feed_url ADD CONSTRAINT feed_url_url_key UNIQUE (url);
@@ -0,0 +1,2 @@
+-- set in the transaction that saves the feed, a retried job finishes instead of saving again
+ALTER TABLE submission ADD COLUMN feed_id integer references feed(id) on delete set null;
\ No newline at end of file
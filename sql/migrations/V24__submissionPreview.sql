@@ -0,0 +1,2 @@
+-- the previewed feed, saved instead of fetching again and dropped with the save
+ALTER TABLE submission ADD COLUMN preview bytea;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- the channel is listened to by `notify::start` on every instance
+SELECT pg_notify('catalogue', '')
\ No newline at end of file
@@ -1 +1 @@
-SELECT numnode(websearch_to_tsquery($1)) as code
\ No newline at end of file
+SELECT numnode(websearch_to_tsquery('simple', $1)) as code
@@ -0,0 +1,4 @@
+UPDATE feed SET
+    img_id = $1,
+    last_modified = CURRENT_TIMESTAMP
+    WHERE id = $2
\ No newline at end of file
@@ -0,0 +1,4 @@
+UPDATE img SET
+    blurhash = $2,
+    color = $3
+WHERE id = $1
\ No newline at end of file
@@ -0,0 +1,15 @@
+-- running jobs that did not report back in time belong to a worker that died
+UPDATE job
+SET status = 'running',
+    attempts = attempts + 1,
+    updated = CURRENT_TIMESTAMP
+WHERE id = (
+    SELECT id
+    FROM job
+    WHERE (status = 'queued' AND run_at <= CURRENT_TIMESTAMP)
+        OR (status = 'running' AND updated < CURRENT_TIMESTAMP - make_interval(secs => $1))
+    ORDER BY run_at
+    LIMIT 1
+    FOR UPDATE SKIP LOCKED
+)
+RETURNING id, kind, payload, attempts, max_attempts
\ No newline at end of file
@@ -0,0 +1,5 @@
+UPDATE job
+SET status = 'done',
+    last_error = NULL,
+    updated = CURRENT_TIMESTAMP
+WHERE id = $1
\ No newline at end of file
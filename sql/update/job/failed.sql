@@ -0,0 +1,7 @@
+-- retried after a backoff until the attempts are used up
+UPDATE job
+SET status = CASE WHEN attempts >= max_attempts THEN 'failed' ELSE 'queued' END::job_status,
+    run_at = CURRENT_TIMESTAMP + make_interval(secs => $3),
+    last_error = $2,
+    updated = CURRENT_TIMESTAMP
+WHERE id = $1
\ No newline at end of file
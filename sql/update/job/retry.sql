@@ -0,0 +1,8 @@
+UPDATE job
+SET status = 'queued',
+    attempts = 0,
+    run_at = CURRENT_TIMESTAMP,
+    updated = CURRENT_TIMESTAMP
+WHERE id = $1 AND status = 'failed'
+RETURNING id, kind, payload, status, attempts, max_attempts,
+          run_at, last_error, created, updated
\ No newline at end of file
@@ -0,0 +1,3 @@
+UPDATE submission
+SET feed_id = $2, preview = NULL
+WHERE id = $1
\ No newline at end of file
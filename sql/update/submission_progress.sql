@@ -0,0 +1,4 @@
+UPDATE submission
+SET progress = $2::jsonb, updated = CURRENT_TIMESTAMP
+WHERE id = $1
+RETURNING updated
\ No newline at end of file
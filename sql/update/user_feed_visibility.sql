@@ -1,5 +1,6 @@
 UPDATE feed 
-SET status = $1
+SET status = $1,
+    last_modified = CURRENT_TIMESTAMP
 WHERE id = $2 
 AND status != 'blocked' AND status != 'queued' 
 AND submitter_id = $3
\ No newline at end of file
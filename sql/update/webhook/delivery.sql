@@ -0,0 +1,7 @@
+UPDATE webhook_delivery
+SET status = $2,
+    attempts = attempts + 1,
+    response_status = $3,
+    last_error = $4,
+    delivered = CASE WHEN $2 = 'delivered'::delivery_status THEN CURRENT_TIMESTAMP ELSE delivered END
+WHERE id = $1
\ No newline at end of file
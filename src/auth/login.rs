@@ -1,6 +1,6 @@
 use super::error::ValidationError;
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct LoginForm {
     pub password: String,
     pub email: String,
@@ -1,8 +1,8 @@
-use crate::{model::Permission, Client};
+use crate::{inc_sql, model::Permission, Client};
 
 use super::error::{AuthError, ValidationError};
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RegisterForm {
     username: String,
@@ -20,9 +20,7 @@ pub async fn new_account(
     let pwd_hash =
         bcrypt::hash(&form.password, 8).map_err(|err| AuthError::Internal(err.into()))?;
 
-    let stmt = trx
-        .prepare("INSERT INTO Account(username, password_hash, email, account_type) Values($1, $2, $3, $4)")
-        .await?;
+    let stmt = trx.prepare_cached(inc_sql!("insert/account")).await?;
     trx.execute(
         &stmt,
         &[&form.username, &pwd_hash, &form.email, &permission],
@@ -0,0 +1,230 @@
+//! In-memory caches for hot read endpoints, shared by all workers through `State`.
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use actix_web::web::Bytes;
+use url::Url;
+
+use crate::model::{category::Category, feed::TinyFeed, page::Page, Author, Meta, Suggestion};
+
+/// Upper bound of all cached preview bodies together.
+const PREVIEW_BYTES: usize = 64 * 1024 * 1024;
+
+/// Small in-memory map whose entries expire after a fixed time.
+#[derive(Clone)]
+pub struct TtlCache<V> {
+    entries: Arc<Mutex<HashMap<String, (Instant, V)>>>,
+    ttl: Duration,
+    capacity: usize,
+    /// Upper bound of the summed weight of all entries and how to weigh one.
+    weight: Option<(usize, fn(&V) -> usize)>,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub ttl_seconds: u64,
+}
+
+impl<V: Clone> TtlCache<V> {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            ttl,
+            capacity,
+            weight: None,
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Bounds the summed `weigh` of all entries by `max`, the oldest entries are
+    /// dropped first and a single entry heavier than `max` is not stored at all.
+    pub fn weighed(mut self, max: usize, weigh: fn(&V) -> usize) -> Self {
+        self.weight = Some((max, weigh));
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<V> {
+        let entries = self.entries.lock().unwrap();
+        let value = entries
+            .get(key)
+            .filter(|(inserted, _)| inserted.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone());
+        let counter = if value.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    pub fn insert(&self, key: String, value: V) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.remove(&key);
+        if let Some((max, weigh)) = self.weight {
+            let weight = weigh(&value);
+            if weight > max {
+                return;
+            }
+            let mut total = entries
+                .values()
+                .map(|(_, value)| weigh(value))
+                .sum::<usize>();
+            while total + weight > max {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, (inserted, _))| *inserted)
+                    .map(|(key, _)| key.clone());
+                match oldest.and_then(|key| entries.remove(&key)) {
+                    Some((_, value)) => total -= weigh(&value),
+                    None => break,
+                }
+            }
+        }
+        if entries.len() >= self.capacity {
+            let ttl = self.ttl;
+            entries.retain(|_, (inserted, _)| inserted.elapsed() < ttl);
+            if entries.len() >= self.capacity {
+                entries.clear();
+            }
+        }
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    /// Takes the entry out of the cache, expired entries count as misses.
+    pub fn remove(&self, key: &str) -> Option<V> {
+        let value = self
+            .entries
+            .lock()
+            .unwrap()
+            .remove(key)
+            .filter(|(inserted, _)| inserted.elapsed() < self.ttl)
+            .map(|(_, value)| value);
+        let counter = if value.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap().len(),
+            ttl_seconds: self.ttl.as_secs(),
+        }
+    }
+}
+
+/// Responses of the read endpoints keyed by their query,
+/// the catalogue caches are dropped whenever a feed is saved or reviewed.
+#[derive(Clone)]
+pub struct ResponseCache {
+    pub meta: TtlCache<Meta>,
+    pub charts: TtlCache<Page<TinyFeed>>,
+    pub recent: TtlCache<Page<TinyFeed>>,
+    pub categories: TtlCache<Vec<Category>>,
+    pub authors: TtlCache<Page<Author>>,
+    pub completions: TtlCache<Vec<Suggestion>>,
+    /// Feed bytes fetched by `/feed/preview`, taken by the following `/feed/new`
+    /// so the submission saves what the user saw. Not part of the catalogue,
+    /// it holds the latest preview of every user, see `save_preview_feed::preview_key`.
+    pub previews: TtlCache<(Url, Bytes)>,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self {
+            meta: TtlCache::new(Duration::from_secs(300), 1),
+            charts: TtlCache::new(Duration::from_secs(60), 256),
+            recent: TtlCache::new(Duration::from_secs(60), 256),
+            categories: TtlCache::new(Duration::from_secs(600), 1),
+            authors: TtlCache::new(Duration::from_secs(300), 256),
+            completions: TtlCache::new(Duration::from_secs(60), 4096),
+            previews: TtlCache::new(Duration::from_secs(600), 512)
+                .weighed(PREVIEW_BYTES, |(_, bytes)| bytes.len()),
+        }
+    }
+
+    /// Only clears this instance, `notify::invalidate_catalogue` reaches all of them.
+    pub fn invalidate_catalogue(&self) {
+        self.meta.clear();
+        self.charts.clear();
+        self.recent.clear();
+        self.categories.clear();
+        self.authors.clear();
+        self.completions.clear();
+    }
+
+    pub fn stats(&self) -> BTreeMap<&'static str, CacheStats> {
+        let mut stats = BTreeMap::new();
+        stats.insert("meta", self.meta.stats());
+        stats.insert("charts", self.charts.stats());
+        stats.insert("recent", self.recent.stats());
+        stats.insert("categories", self.categories.stats());
+        stats.insert("authors", self.authors.stats());
+        stats.insert("completions", self.completions.stats());
+        stats.insert("previews", self.previews.stats());
+        stats
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_remove_takes_the_entry() {
+        let cache = TtlCache::new(Duration::from_secs(60), 4);
+        cache.insert("a".to_string(), 1);
+        assert_eq!(cache.remove("a"), Some(1));
+        assert_eq!(cache.remove("a"), None);
+        assert_eq!(cache.get("a"), None);
+
+        let expired = TtlCache::new(Duration::from_secs(0), 4);
+        expired.insert("a".to_string(), 1);
+        assert_eq!(expired.remove("a"), None);
+        let stats = expired.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (0, 1, 0));
+    }
+
+    #[test]
+    fn test_weighed_drops_oldest_entries() {
+        let cache =
+            TtlCache::new(Duration::from_secs(60), 16).weighed(10, |value: &Vec<u8>| value.len());
+        cache.insert("a".to_string(), vec![0; 4]);
+        cache.insert("b".to_string(), vec![0; 4]);
+        cache.insert("c".to_string(), vec![0; 4]);
+        assert_eq!(cache.get("a"), None);
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_some());
+
+        // replacing an entry does not count its old weight
+        cache.insert("c".to_string(), vec![0; 6]);
+        assert!(cache.get("b").is_some());
+
+        cache.insert("huge".to_string(), vec![0; 11]);
+        assert_eq!(cache.get("huge"), None);
+        assert_eq!(cache.stats().entries, 2);
+    }
+}
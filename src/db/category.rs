@@ -1,63 +1,60 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
-use futures_util::future;
-use tokio_postgres::Transaction;
+use deadpool_postgres::Transaction;
 
 use crate::{handler::error::ApiError, inc_sql, model::category::Category, Client};
 
-use super::rows_into_vec;
+use super::QueryRows;
 
-pub async fn insert_subcategories(
+/// Creates the missing categories and returns the id of every given description.
+/// `parent_ids` runs parallel to `descriptions`.
+async fn insert_or_get_category_ids(
     trx: &Transaction<'_>,
-    parent_category: i32,
-    subcategories: &[&str],
-) -> Result<Vec<i32>, tokio_postgres::Error> {
-    future::try_join_all(
-        subcategories
-            .iter()
-            .map(|child| insert_or_get_category_id(trx, child, Some(parent_category))),
-    )
-    .await
-}
-
-pub async fn insert_or_get_category_id(
-    trx: &Transaction<'_>,
-    category: &str,
-    parent_id: Option<i32>,
-) -> Result<i32, tokio_postgres::Error> {
-    const WITHOUT_PARENT: &str = inc_sql!("insert/category");
-    const WITH_PARENT: &str = inc_sql!("insert/category_with_parent");
-    let stmnt = trx
-        .prepare(if parent_id.is_some() {
-            WITH_PARENT
-        } else {
-            WITHOUT_PARENT
-        })
-        .await?;
-
-    let row = match parent_id {
-        Some(parent_id) => trx.query_one(&stmnt, &[&category, &parent_id]).await?,
-        None => trx.query_one(&stmnt, &[&category]).await?,
-    };
-    Ok(row.get("id"))
+    descriptions: &[&str],
+    parent_ids: &[Option<i32>],
+) -> Result<HashMap<String, i32>, tokio_postgres::Error> {
+    if descriptions.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let stmnt = trx.prepare_cached(inc_sql!("insert/categories")).await?;
+    let rows = trx.query(&stmnt, &[&descriptions, &parent_ids]).await?;
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("description"), row.get("id")))
+        .collect())
 }
 
+/// Links the feed to its categories and subcategories in three statements,
+/// independent of how many the feed declares.
 pub async fn insert_feed_catagories(
     trx: &Transaction<'_>,
     categories: &BTreeMap<&str, Vec<&str>>,
     feed_id: i32,
 ) -> Result<(), tokio_postgres::Error> {
-    let stmnt = trx
-        .prepare("INSERT INTO feed_category (feed_id, category_id) VALUES($1, $2)")
-        .await?;
+    let parents = categories.keys().copied().collect::<Vec<_>>();
+    let parent_ids = insert_or_get_category_ids(trx, &parents, &vec![None; parents.len()]).await?;
+
+    let (children, children_parent_ids): (Vec<&str>, Vec<Option<i32>>) = categories
+        .iter()
+        .flat_map(|(parent, children)| {
+            let parent_id = parent_ids.get(*parent).copied();
+            children.iter().map(move |child| (*child, parent_id))
+        })
+        .unzip();
+    let child_ids = insert_or_get_category_ids(trx, &children, &children_parent_ids).await?;
 
-    for (parent, children) in categories {
-        let parent_id = insert_or_get_category_id(trx, parent, None).await?;
-        trx.execute(&stmnt, &[&feed_id, &parent_id]).await?;
-        for child_id in insert_subcategories(trx, parent_id, children).await? {
-            trx.execute(&stmnt, &[&feed_id, &child_id]).await?;
-        }
+    let mut category_ids = parent_ids
+        .values()
+        .chain(child_ids.values())
+        .copied()
+        .collect::<Vec<i32>>();
+    category_ids.sort_unstable();
+    category_ids.dedup();
+    if category_ids.is_empty() {
+        return Ok(());
     }
+    let stmnt = trx.prepare_cached(inc_sql!("insert/feed_category")).await?;
+    trx.execute(&stmnt, &[&feed_id, &category_ids]).await?;
 
     Ok(())
 }
@@ -66,31 +63,43 @@ pub async fn get_categories_for_feed(
     client: &Client,
     feed_id: i32,
 ) -> Result<Vec<Category>, ApiError> {
-    let categories_stmnt = client.prepare(inc_sql!("get/category/by_feed_id")).await?;
-    let categories_rows = client.query(&categories_stmnt, &[&feed_id]).await?;
-    let mut categories = Vec::new();
-    for row in &categories_rows {
-        let subcategories_stmnt = client
-            .prepare(inc_sql!("get/category/sub_by_feed_id"))
-            .await?;
-        let category_id: i32 = row.get("id");
-        let subcategories_rows = client
-            .query(&subcategories_stmnt, &[&feed_id, &category_id])
-            .await?;
-        let subcategories = rows_into_vec(subcategories_rows);
-        categories.push(Category::from(row, subcategories));
-    }
+    let mut categories = categories_for_feeds(client, &[feed_id]).await?;
+    Ok(categories.remove(&feed_id).unwrap_or_default())
+}
 
+/// Categories with their subcategories for every feed in one query.
+pub async fn categories_for_feeds<C: QueryRows>(
+    client: &C,
+    feed_ids: &[i32],
+) -> Result<HashMap<i32, Vec<Category>>, tokio_postgres::Error> {
+    let mut categories: HashMap<i32, Vec<Category>> = HashMap::new();
+    if feed_ids.is_empty() {
+        return Ok(categories);
+    }
+    let rows = client
+        .query_rows(inc_sql!("get/category/by_feed_ids"), &[&feed_ids])
+        .await?;
+    for row in &rows {
+        let subcategories = serde_json::from_value(row.get("subcategories")).unwrap_or_default();
+        categories
+            .entry(row.get("feed_id"))
+            .or_default()
+            .push(Category::from(row, subcategories));
+    }
     Ok(categories)
 }
 
 pub async fn get_by_id_or_name(client: &Client, path: &str) -> Result<Category, ApiError> {
     let result = if let Ok(category_id) = path.parse::<i32>() {
-        let stmnt = client.prepare(inc_sql!("get/category/by_id")).await?;
+        let stmnt = client
+            .prepare_cached(inc_sql!("get/category/by_id"))
+            .await?;
         client.query_one(&stmnt, &[&category_id]).await
     } else {
         let category_name = path;
-        let stmnt = client.prepare(inc_sql!("get/category/by_name")).await?;
+        let stmnt = client
+            .prepare_cached(inc_sql!("get/category/by_name"))
+            .await?;
         client.query_one(&stmnt, &[&category_name]).await
     };
     let row = result.map_err(|_e| ApiError::CategoryNotFound(path.to_string()))?;
@@ -102,8 +111,8 @@ pub async fn get_by_id_or_name(client: &Client, path: &str) -> Result<Category,
 }
 
 pub async fn get_all(client: &Client) -> Result<Vec<Category>, ApiError> {
-    let stmnt = inc_sql!("get/category/all");
-    let rows = client.query(stmnt, &[]).await?;
+    let stmnt = client.prepare_cached(inc_sql!("get/category/all")).await?;
+    let rows = client.query(&stmnt, &[]).await?;
     let categories = rows
         .iter()
         .map(|row| {
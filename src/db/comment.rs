@@ -1,6 +1,9 @@
 use crate::{
     inc_sql,
-    model::{Comment, NewComment},
+    model::{
+        page::{Page, Position},
+        Comment, NewComment,
+    },
     Client,
 };
 
@@ -8,7 +11,7 @@ pub async fn insert(
     client: &mut Client,
     comment: NewComment,
 ) -> Result<Comment, tokio_postgres::Error> {
-    let stmnt = client.prepare(inc_sql!("insert/comment")).await?;
+    let stmnt = client.prepare_cached(inc_sql!("insert/comment")).await?;
     let trx = client.transaction().await?;
 
     let row = trx
@@ -21,14 +24,13 @@ pub async fn insert(
     Ok(Comment::from(row))
 }
 
-pub async fn get(client: &Client, feed_id: i32) -> Result<Vec<Comment>, tokio_postgres::Error> {
-    let stmnt = client.prepare(inc_sql!("get/comment")).await?;
-    let rows = client.query(&stmnt, &[&feed_id]).await?;
+pub async fn get(
+    client: &Client,
+    feed_id: i32,
+    position: &Position,
+) -> Result<Page<Comment>, tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("get/comment")).await?;
+    let rows = client.query(&stmnt, &position.params(&[&feed_id])).await?;
 
-    let comments = rows
-        .into_iter()
-        .map(|row| Comment::from(row))
-        .collect::<Vec<_>>();
-
-    Ok(comments)
+    Ok(position.page(rows).map(Comment::from))
 }
@@ -0,0 +1,217 @@
+//! Finds the feed a submission duplicates. Shows are matched by their normalised URLs,
+//! including the `itunes:new-feed-url` they announced, by `podcast:guid` and by the
+//! GUIDs of their episodes. Titles are not unique and take no part.
+use deadpool_postgres::Transaction;
+use url::Url;
+
+use crate::{
+    handler::save_preview_feed::error::Field, inc_sql, model::preview::feed::FeedPreview, Client,
+};
+
+/// Share of the episode GUIDs of the submitted and of the saved feed that have to match
+/// to count as the same show.
+const EPISODE_OVERLAP: f64 = 0.5;
+/// Fewer matching GUIDs are taken for a chance, like a trailer shared between shows.
+const MIN_EPISODE_OVERLAP: i64 = 3;
+
+/// Query parameters of known trackers, hosts use generic names like `source` for content.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "mc_cid", "mc_eid"];
+
+/// The URL without what does not change the document it points to:
+/// scheme, a `www.` prefix, default port, trailing slashes, fragment and tracking parameters.
+/// The remaining query parameters are sorted.
+pub fn normalize_url(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let mut normalized = match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    };
+    normalized.push_str(url.path().trim_end_matches('/'));
+
+    let mut params = url
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_ref()))
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>();
+    if !params.is_empty() {
+        params.sort();
+        normalized.push('?');
+        normalized.push_str(&params.join("&"));
+    }
+    normalized
+}
+
+/// Normalised URLs a feed is known under.
+pub fn feed_urls(feed: &FeedPreview<'_>) -> Vec<String> {
+    let mut urls = std::iter::once(&feed.url)
+        .chain(feed.new_feed_url.as_ref())
+        .map(normalize_url)
+        .collect::<Vec<_>>();
+    urls.dedup();
+    urls
+}
+
+/// Whether a saved feed with `episodes` GUIDs, `overlap` of them among the `guids`
+/// submitted episode GUIDs, is the same show. A compilation of a few episodes of a
+/// long running show shares all of its GUIDs but only a small part of the show's.
+fn is_same_show(overlap: i64, guids: usize, episodes: i64) -> bool {
+    let share = |total: f64| (total * EPISODE_OVERLAP).ceil() as i64;
+    overlap >= MIN_EPISODE_OVERLAP
+        && overlap >= share(guids as f64)
+        && overlap >= share(episodes as f64)
+}
+
+/// The feed known under one of the normalised `urls`.
+pub async fn by_url(
+    client: &Client,
+    urls: &[String],
+) -> Result<Option<i32>, tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/feed/duplicate/by_url"))
+        .await?;
+    let row = client.query_opt(&stmnt, &[&urls]).await?;
+    Ok(row.map(|row| row.get("feed_id")))
+}
+
+/// The id of the saved feed `feed` duplicates and what gave it away.
+pub async fn find(
+    client: &Client,
+    feed: &FeedPreview<'_>,
+) -> Result<Option<(i32, Field)>, tokio_postgres::Error> {
+    if let Some(feed_id) = by_url(client, &feed_urls(feed)).await? {
+        return Ok(Some((feed_id, Field::Url)));
+    }
+
+    if let Some(guid) = feed.podcast_guid {
+        let stmnt = client
+            .prepare_cached(inc_sql!("get/feed/duplicate/by_podcast_guid"))
+            .await?;
+        if let Some(row) = client.query_opt(&stmnt, &[&guid]).await? {
+            return Ok(Some((row.get("feed_id"), Field::PodcastGuid)));
+        }
+    }
+
+    let mut guids = feed
+        .episodes
+        .iter()
+        .filter_map(|episode| episode.guid.as_deref())
+        .collect::<Vec<_>>();
+    guids.sort_unstable();
+    guids.dedup();
+    if guids.is_empty() {
+        return Ok(None);
+    }
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/feed/duplicate/by_episode_guids"))
+        .await?;
+    let duplicate = client
+        .query_opt(&stmnt, &[&guids])
+        .await?
+        .filter(|row| is_same_show(row.get("overlap"), guids.len(), row.get("episodes")))
+        .map(|row| (row.get("feed_id"), Field::EpisodeGuids));
+    Ok(duplicate)
+}
+
+pub async fn insert_feed_urls(
+    trx: &Transaction<'_>,
+    feed_id: i32,
+    urls: &[String],
+) -> Result<(), tokio_postgres::Error> {
+    let stmnt = trx.prepare_cached(inc_sql!("insert/feed_url")).await?;
+    trx.execute(&stmnt, &[&feed_id, &urls]).await?;
+    Ok(())
+}
+
+/// Records the URL of feeds saved before the URL history existed.
+pub async fn backfill_feed_urls(client: &mut Client) -> Result<(), tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/feed/without_url_history"))
+        .await?;
+    let rows = client.query(&stmnt, &[]).await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let known = client
+        .prepare_cached(inc_sql!("get/feed/duplicate/by_url"))
+        .await?;
+    let trx = client.transaction().await?;
+    for row in &rows {
+        let url: String = row.get("url");
+        let urls = match Url::parse(&url) {
+            Ok(url) => vec![normalize_url(&url)],
+            Err(_) => continue,
+        };
+        // URLs are unique, a show saved twice keeps it on its first feed
+        if trx.query_opt(&known, &[&urls]).await?.is_some() {
+            continue;
+        }
+        insert_feed_urls(&trx, row.get("id"), &urls).await?;
+    }
+    trx.commit().await?;
+    log::info!("recorded the url of {} feeds", rows.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn normalized(url: &str) -> String {
+        normalize_url(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        let expected = "example.com/feed.xml";
+        assert_eq!(normalized("https://example.com/feed.xml"), expected);
+        assert_eq!(normalized("http://www.Example.com/feed.xml/"), expected);
+        assert_eq!(normalized("https://example.com:443/feed.xml#top"), expected);
+        assert_eq!(
+            normalized("https://example.com/feed.xml?utm_source=x&fbclid=y"),
+            expected
+        );
+        assert_eq!(
+            normalized("https://example.com/feed.xml?b=2&utm_medium=x&a=1"),
+            "example.com/feed.xml?a=1&b=2"
+        );
+        assert_eq!(normalized("https://example.com:8080/"), "example.com:8080");
+        assert_ne!(
+            normalized("https://example.com/feed.xml?format=mp3"),
+            normalized("https://example.com/feed.xml?format=aac")
+        );
+        // not a tracker on every host
+        assert_ne!(
+            normalized("https://example.com/feed.xml?source=premium"),
+            normalized("https://example.com/feed.xml?source=free")
+        );
+        assert_ne!(
+            normalized("https://example.com/feed.xml?ref=main"),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_half_of_the_episodes_make_the_same_show() {
+        assert!(is_same_show(3, 3, 3));
+        assert!(is_same_show(3, 6, 5));
+        assert!(!is_same_show(3, 7, 5));
+        assert!(is_same_show(5, 10, 10));
+        assert!(!is_same_show(4, 10, 8));
+        // a grown show still matches the feed saved before
+        assert!(is_same_show(100, 150, 100));
+    }
+
+    #[test]
+    fn test_few_matching_episodes_are_no_duplicate() {
+        assert!(!is_same_show(0, 1, 1));
+        assert!(!is_same_show(1, 1, 1));
+        assert!(!is_same_show(2, 2, 2));
+    }
+
+    #[test]
+    fn test_a_compilation_is_not_the_show() {
+        assert!(!is_same_show(10, 10, 500));
+        assert!(!is_same_show(10, 500, 10));
+    }
+}
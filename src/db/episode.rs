@@ -1,76 +1,58 @@
 use crate::{inc_sql, model::preview::episode::Episode};
-use futures_util::future;
-use tokio_postgres::{Client, Transaction};
+use deadpool_postgres::Transaction;
+use std::collections::HashMap;
 
+/// Episodes per statement, keeps a single bind message of huge feeds reasonably small.
+const INSERT_CHUNK: usize = 1000;
+
+/// Writes all episodes with one multi-row INSERT per chunk,
+/// every column is bound as an array and expanded with `unnest`.
+/// `on_stored` receives the running total after each chunk.
 pub async fn insert_episodes(
     trx: &Transaction<'_>,
     feed_id: i32,
     episodes: &[Episode],
-) -> Result<(), tokio_postgres::Error> {
-    future::try_join_all(
-        episodes
-            .iter()
-            .map(|ep| insert_one_episode(trx, feed_id, ep)),
-    )
-    .await?;
-
-    Ok(())
-}
-
-pub async fn insert_one_episode(
-    trx: &Transaction<'_>,
-    feed_id: i32,
-    ep: &Episode,
-) -> Result<(), tokio_postgres::Error> {
-    let stmnt = trx.prepare(inc_sql!("insert/episode")).await?;
-    trx.execute(
-        &stmnt,
-        &[
-            &ep.title,
-            &ep.description,
-            &ep.published,
-            &ep.explicit,
-            &ep.keywords,
-            &ep.duration,
-            &ep.show_notes,
-            &ep.url(),
-            &ep.media_url(),
-            &feed_id,
-            &ep.guid,
-            &ep.enclosure.length,
-            &ep.enclosure.mime_type.to_string(),
-        ],
-    )
-    .await?;
+    img_ids: &HashMap<&str, i32>,
+    on_stored: &dyn Fn(usize),
+) -> Result<u64, tokio_postgres::Error> {
+    let stmnt = trx.prepare_cached(inc_sql!("insert/episodes")).await?;
+    let mut inserted = 0;
+    for chunk in episodes.chunks(INSERT_CHUNK) {
+        // text[][] can not hold keyword lists of different lengths, so they travel as jsonb
+        let keywords = column(chunk, |ep| {
+            ep.keywords.as_ref().map(|k| serde_json::json!(k))
+        });
+        let mime_types = column(chunk, |ep| ep.enclosure.mime_type.to_string());
+        let img_ids = column(chunk, |ep| {
+            ep.img_url().and_then(|url| img_ids.get(url)).copied()
+        });
+        inserted += trx
+            .execute(
+                &stmnt,
+                &[
+                    &feed_id,
+                    &column(chunk, |ep| ep.title.as_str()),
+                    &column(chunk, |ep| ep.description.as_deref()),
+                    &column(chunk, |ep| ep.published),
+                    &column(chunk, |ep| ep.explicit),
+                    &keywords,
+                    &column(chunk, |ep| ep.duration),
+                    &column(chunk, |ep| ep.show_notes.as_deref()),
+                    &column(chunk, |ep| ep.url()),
+                    &column(chunk, |ep| ep.media_url()),
+                    &column(chunk, |ep| ep.guid.as_deref()),
+                    &column(chunk, |ep| ep.enclosure.length),
+                    &mime_types,
+                    &img_ids,
+                ],
+            )
+            .await?;
+        on_stored(inserted as usize);
+    }
 
-    Ok(())
+    Ok(inserted)
 }
-pub async fn episode_offset(
-    client: &Client,
-    episodes: &[Episode],
-    feed_id: &i32,
-) -> Result<Option<i64>, tokio_postgres::Error> {
-    if 50 > episodes.len() {
-        return Ok(None);
-    }
 
-    let max_stmnt = client
-        .prepare(
-            r#"
-                SELECT max(episode.id) as max
-                FROM episode 
-                where feed_id = $1"#,
-        )
-        .await?;
-    let max_episode_id = client
-        .query_one(&max_stmnt, &[&feed_id])
-        .await?
-        .get::<_, i64>("max");
-    let max_row_id = episodes.iter().map(|e| e.id).max();
-    let ret = if matches!(max_row_id, Some(id) if id >= max_episode_id) {
-        None
-    } else {
-        max_row_id
-    };
-    Ok(ret)
+fn column<'a, T>(episodes: &'a [Episode], field: impl Fn(&'a Episode) -> T) -> Vec<T> {
+    episodes.iter().map(field).collect()
 }
@@ -1,15 +1,74 @@
-use crate::handler::save_preview_feed::error::PreviewSaveError;
+use crate::handler::save_preview_feed::error::{Field, PreviewSaveError};
 use crate::model::preview::feed::FeedPreview;
 use crate::Client;
 use crate::{img_cache::RowImg, inc_sql};
+use deadpool_postgres::Transaction;
 use futures_util::future;
-use tokio_postgres::Transaction;
+use std::collections::HashMap;
 
 use super::{
-    category::insert_feed_catagories, episode::insert_episodes, insert_or_get_author_id,
-    insert_or_get_img_id, insert_or_get_language_id,
+    category::insert_feed_catagories,
+    duplicate::{by_url, feed_urls, insert_feed_urls},
+    episode::insert_episodes,
+    insert_or_get_author_id, insert_or_get_img_id, insert_or_get_language_id, submission,
 };
 
+#[derive(Debug)]
+pub struct FeedImg {
+    pub url: String,
+    pub img_id: Option<i32>,
+    pub link: Option<String>,
+}
+
+pub async fn img_link(client: &Client, feed_id: i32) -> Result<FeedImg, tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("get/feed/img_link")).await?;
+    let row = client.query_one(&stmnt, &[&feed_id]).await?;
+    Ok(FeedImg {
+        url: row.get("url"),
+        img_id: row.get("img_id"),
+        link: row.get("img"),
+    })
+}
+
+/// Online feeds whose artwork was not refreshed within `interval`, oldest first.
+pub async fn artwork_refresh_due(
+    client: &Client,
+    interval: std::time::Duration,
+) -> Result<Vec<i32>, tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/feed/artwork_refresh_due"))
+        .await?;
+    let rows = client.query(&stmnt, &[&interval.as_secs_f64()]).await?;
+    Ok(rows.iter().map(|row| row.get("id")).collect())
+}
+
+/// Points the feed at `img` and deletes the previous img row if nothing else uses it.
+/// Returns the id of `img` and the file name of the released image so it can be
+/// removed from the cache.
+pub async fn switch_img(
+    client: &mut Client,
+    feed_id: i32,
+    old_img_id: Option<i32>,
+    img: &RowImg<'_>,
+) -> Result<(i32, Option<String>), PreviewSaveError> {
+    let trx = client.transaction().await?;
+    let img_id = insert_or_get_img_id(&trx, img).await?;
+    let stmnt = trx.prepare_cached(inc_sql!("update/feed_img")).await?;
+    trx.execute(&stmnt, &[&img_id, &feed_id]).await?;
+
+    let released = match old_img_id {
+        Some(old_id) if old_id != img_id => {
+            let stmnt = trx.prepare_cached(inc_sql!("delete/unused_img")).await?;
+            trx.query_opt(&stmnt, &[&old_id])
+                .await?
+                .map(|row| row.get("filename"))
+        }
+        _ => None,
+    };
+    trx.commit().await?;
+    Ok((img_id, released))
+}
+
 #[derive(Debug)]
 struct Context<'a> {
     user: &'a i32,
@@ -24,21 +83,59 @@ pub async fn save(
     feed_content: &FeedPreview<'_>,
     user_id: i32,
     img: Option<RowImg<'_>>,
+    episode_imgs: &[RowImg<'_>],
+    on_stored: &dyn Fn(usize),
+    submission_id: i64,
 ) -> Result<i32, PreviewSaveError> {
     let trx = client.transaction().await?;
-    let author_id = insert_or_get_author_id(&trx, feed_content.author_name).await;
-    let language = if let Some(lang) = feed_content.language {
-        insert_or_get_language_id(&trx, lang).await.ok()
-    } else {
-        None
+    let feed_id = match insert(&trx, feed_content, user_id, img, episode_imgs, on_stored).await {
+        Ok(feed_id) => feed_id,
+        // a submission of the same show was saved after the duplicate check
+        Err(PreviewSaveError::Duplicate(Field::Url)) => {
+            drop(trx);
+            return Err(match by_url(client, &feed_urls(feed_content)).await? {
+                Some(feed_id) => PreviewSaveError::Exists(feed_id, Field::Url),
+                None => PreviewSaveError::Duplicate(Field::Url),
+            });
+        }
+        Err(err) => return Err(err),
     };
+    submission::set_feed(&trx, submission_id, feed_id).await?;
+    trx.commit().await?;
+    Ok(feed_id)
+}
 
-    let img_id: Option<i32> = if let Some(img) = &img {
-        insert_or_get_img_id(&trx, img).await.ok()
-    } else {
-        None
+/// Writes the feed with its episodes and categories without committing `trx`,
+/// `on_stored` is called with the number of episodes written so far.
+pub async fn insert(
+    trx: &Transaction<'_>,
+    feed_content: &FeedPreview<'_>,
+    user_id: i32,
+    img: Option<RowImg<'_>>,
+    episode_imgs: &[RowImg<'_>],
+    on_stored: &dyn Fn(usize),
+) -> Result<i32, PreviewSaveError> {
+    let author_id = insert_or_get_author_id(trx, feed_content.author_name).await;
+    // a failed statement aborts `trx`, so errors are returned instead of skipped
+    let language = match feed_content.language {
+        Some(lang) => Some(insert_or_get_language_id(trx, lang).await?),
+        None => None,
     };
 
+    let img_id = match &img {
+        Some(img) => Some(insert_or_get_img_id(trx, img).await?),
+        None => None,
+    };
+
+    let mut img_ids = HashMap::new();
+    if let (Some(img), Some(id)) = (&img, img_id) {
+        img_ids.insert(img.link.as_str(), id);
+    }
+    for episode_img in episode_imgs {
+        let id = insert_or_get_img_id(trx, episode_img).await?;
+        img_ids.insert(episode_img.link.as_str(), id);
+    }
+
     let context = Context {
         user: &user_id,
         author: &author_id,
@@ -46,13 +143,13 @@ pub async fn save(
         img: &img_id,
         feed: feed_content,
     };
-    let feed_id = insert_feed(&trx, &context).await?;
-    future::try_join(
-        insert_feed_catagories(&trx, &feed_content.categories, feed_id),
-        insert_episodes(&trx, feed_id, &feed_content.episodes),
+    let feed_id = insert_feed(trx, &context).await?;
+    future::try_join3(
+        insert_feed_urls(trx, feed_id, &feed_urls(feed_content)),
+        insert_feed_catagories(trx, &feed_content.categories, feed_id),
+        insert_episodes(trx, feed_id, &feed_content.episodes, &img_ids, on_stored),
     )
     .await?;
-    trx.commit().await?;
     Ok(feed_id)
 }
 
@@ -60,7 +157,7 @@ async fn insert_feed(
     trx: &Transaction<'_>,
     context: &Context<'_>,
 ) -> Result<i32, PreviewSaveError> {
-    let stmnt = trx.prepare(inc_sql!("insert/feed")).await?;
+    let stmnt = trx.prepare_cached(inc_sql!("insert/feed")).await?;
 
     let row = trx
         .query_one(
@@ -75,8 +172,112 @@ async fn insert_feed(
                 &context.feed.url(),
                 context.language,
                 &context.feed.link_web(),
+                &context.feed.podcast_guid,
             ],
         )
         .await?;
     Ok(row.get("id"))
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Instant;
+
+    use super::*;
+
+    const FIXTURE_EPISODES: usize = 5000;
+
+    fn fixture_feed(episodes: usize) -> String {
+        let items = (0..episodes)
+            .map(|i| {
+                format!(
+                    r#"<item>
+    <title>Episode {i}</title>
+    <description>Description of episode {i}</description>
+    <link>https://bulk.example.com/episode/{i}</link>
+    <guid>bulk-episode-{i}</guid>
+    <pubDate>Mon, 01 Mar 2021 10:00:00 +0000</pubDate>
+    <enclosure url="https://bulk.example.com/episode/{i}.mp3" length="{i}" type="audio/mpeg"/>
+    <itunes:duration>00:42:00</itunes:duration>
+    <itunes:keywords>bulk, episode{}</itunes:keywords>
+</item>"#,
+                    i % 7,
+                    i = i
+                )
+            })
+            .collect::<String>();
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+<channel>
+    <title>Bulk fixture {}</title>
+    <link>https://bulk.example.com</link>
+    <description>Feed with many episodes</description>
+    <language>en</language>
+    <itunes:author>Bulk Author</itunes:author>
+    <itunes:category text="Technology"><itunes:category text="Software How-To"/></itunes:category>
+    <itunes:category text="Education"/>
+    {}
+</channel>
+</rss>"#,
+            chrono::Utc::now().timestamp_nanos(),
+            items
+        )
+    }
+
+    /// Logs how long the insert takes, CI runs it with `--nocapture` to show the time.
+    /// Runner speeds vary too much for a bound, the counts are what is asserted.
+    #[tokio::test]
+    #[ignore = "needs the DB_* environment of a migrated database, runs in CI"]
+    async fn test_save_time_for_large_feed() {
+        let _ = env_logger::builder()
+            .is_test(true)
+            .filter_level(log::LevelFilter::Info)
+            .try_init();
+        let xml = fixture_feed(FIXTURE_EPISODES);
+        let channel = rss::Channel::read_from(xml.as_bytes()).unwrap();
+        let url = reqwest::Url::parse("https://bulk.example.com/feed.xml").unwrap();
+        let preview = FeedPreview::parse(&channel, url);
+        assert_eq!(preview.episodes.len(), FIXTURE_EPISODES);
+
+        let pool = super::super::util::connect_and_migrate().await.unwrap();
+        let mut client = pool.get().await.unwrap();
+        // everything is rolled back when the transaction is dropped
+        let trx = client.transaction().await.unwrap();
+        let user_id: i32 = trx
+            .query_one(
+                "INSERT INTO Account(username, password_hash, email) \
+                 VALUES('bulk', 'bulk', 'bulk@example.com') RETURNING id",
+                &[],
+            )
+            .await
+            .unwrap()
+            .get("id");
+
+        let start = Instant::now();
+        let feed_id = insert(&trx, &preview, user_id, None, &[], &|_| {})
+            .await
+            .unwrap();
+        let elapsed = start.elapsed();
+        log::info!("saved {} episodes in {:?}", FIXTURE_EPISODES, elapsed);
+
+        let episodes: i64 = trx
+            .query_one(
+                "SELECT count(*) FROM episode WHERE feed_id = $1",
+                &[&feed_id],
+            )
+            .await
+            .unwrap()
+            .get(0);
+        assert_eq!(episodes, FIXTURE_EPISODES as i64);
+        let categories: i64 = trx
+            .query_one(
+                "SELECT count(*) FROM feed_category WHERE feed_id = $1",
+                &[&feed_id],
+            )
+            .await
+            .unwrap()
+            .get(0);
+        assert_eq!(categories, 3);
+    }
+}
@@ -1,16 +1,45 @@
 pub mod category;
 pub mod comment;
+pub mod duplicate;
 pub mod episode;
 pub mod feed;
+pub mod queue;
+pub mod search;
+pub mod statement;
+pub mod submission;
 pub mod subscription;
+pub mod webhook;
 
 pub mod util;
 use crate::handler::error::ApiError;
 use crate::inc_sql;
 use crate::{handler::save_preview_feed::error::PreviewSaveError, img_cache::RowImg, Client};
 use deadpool_postgres::Transaction;
+use futures_util::future::LocalBoxFuture;
 use tokio_pg_mapper::FromTokioPostgresRow;
-use tokio_postgres::Row;
+use tokio_postgres::{types::ToSql, Row};
+
+/// The part of the client the batch loaders need, so tests can count the round trips.
+pub trait QueryRows {
+    fn query_rows<'a>(
+        &'a self,
+        sql: &'a str,
+        params: &'a [&'a (dyn ToSql + Sync)],
+    ) -> LocalBoxFuture<'a, Result<Vec<Row>, tokio_postgres::Error>>;
+}
+
+impl QueryRows for Client {
+    fn query_rows<'a>(
+        &'a self,
+        sql: &'a str,
+        params: &'a [&'a (dyn ToSql + Sync)],
+    ) -> LocalBoxFuture<'a, Result<Vec<Row>, tokio_postgres::Error>> {
+        Box::pin(async move {
+            let stmnt = self.prepare_cached(sql).await?;
+            self.query(&stmnt, params).await
+        })
+    }
+}
 
 pub fn rows_into_vec<T>(row: Vec<Row>) -> Vec<T>
 where
@@ -21,43 +50,67 @@ where
         .collect::<Vec<_>>()
 }
 
-pub async fn feed_exits(
-    client: &Client,
-    title: &str,
-    url: &str,
-) -> Result<bool, tokio_postgres::Error> {
-    let stmnt = client.prepare(inc_sql!("get/feed/exists")).await?;
-    Ok(client.query_one(&stmnt, &[&title, &url]).await.is_ok())
-}
-
 pub async fn is_moderator(client: &Client, id: i32) -> Result<bool, ApiError> {
     let stmnt = client
-        .prepare("SELECT id from Account WHERE id = $1 AND account_type = 'admin'")
+        .prepare_cached(inc_sql!("get/account_is_moderator"))
         .await?;
     Ok(client.query_one(&stmnt, &[&id]).await.is_ok())
 }
 
-async fn insert_or_get_img_id(
+pub async fn insert_or_get_img_id(
     trx: &Transaction<'_>,
     img: &RowImg<'_>,
 ) -> Result<i32, PreviewSaveError> {
-    let stmnt = trx.prepare(inc_sql!("insert/img")).await?;
+    let stmnt = trx.prepare_cached(inc_sql!("insert/img")).await?;
 
     let row = trx
         .query_one(
             &stmnt,
-            &[&img.link.clone().as_str(), &img.hash, &img.file_name],
+            &[
+                &img.link.clone().as_str(),
+                &img.hash,
+                &img.file_name,
+                &img.blurhash,
+                &img.color,
+            ],
         )
         .await?;
 
     Ok(row.get("id"))
 }
 
+/// File name of an image that has no placeholder yet.
+pub async fn img_without_placeholder(
+    client: &Client,
+    img_id: i32,
+) -> Result<Option<String>, tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/img/without_placeholder"))
+        .await?;
+    let row = client.query_opt(&stmnt, &[&img_id]).await?;
+    Ok(row.map(|row| row.get("filename")))
+}
+
+pub async fn update_img_placeholder(
+    client: &Client,
+    img_id: i32,
+    blurhash: &str,
+    color: &str,
+) -> Result<(), tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("update/img_placeholder"))
+        .await?;
+    client
+        .execute(&stmnt, &[&img_id, &blurhash, &color])
+        .await?;
+    Ok(())
+}
+
 pub async fn insert_or_get_language_id(
     trx: &Transaction<'_>,
     language: &str,
 ) -> Result<i32, tokio_postgres::Error> {
-    let stmnt = trx.prepare(inc_sql!("insert/language")).await?;
+    let stmnt = trx.prepare_cached(inc_sql!("insert/language")).await?;
     let row = trx.query_one(&stmnt, &[&language]).await?;
     Ok(row.get("id"))
 }
@@ -67,7 +120,7 @@ pub async fn insert_or_get_author_id(
     author_name: Option<&str>,
 ) -> Option<i32> {
     if let Some(name) = author_name {
-        let stmnt = trx.prepare(inc_sql!("insert/author")).await.ok();
+        let stmnt = trx.prepare_cached(inc_sql!("insert/author")).await.ok();
         if let Some(s) = stmnt {
             return trx
                 .query_one(&s, &[&name])
@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use tokio_pg_mapper::FromTokioPostgresRow;
+
+use crate::{
+    inc_sql,
+    model::{
+        page::{Page, Position},
+        queue::{JobStatus, QueuedJob},
+    },
+    Client,
+};
+
+/// A job a worker has taken, it stays `running` until it is reported back.
+#[derive(Debug)]
+pub struct Claimed {
+    pub id: i64,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub max_attempts: i32,
+}
+
+pub async fn insert(
+    client: &Client,
+    kind: &str,
+    payload: &serde_json::Value,
+    run_at: DateTime<Utc>,
+    max_attempts: i32,
+) -> Result<i64, tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("insert/job")).await?;
+    let row = client
+        .query_one(&stmnt, &[&kind, payload, &run_at, &max_attempts])
+        .await?;
+    Ok(row.get("id"))
+}
+
+/// Takes the next due job, jobs `running` for longer than `timeout` are taken again.
+pub async fn claim(
+    client: &Client,
+    timeout: Duration,
+) -> Result<Option<Claimed>, tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("update/job/claim")).await?;
+    let row = client.query_opt(&stmnt, &[&timeout.as_secs_f64()]).await?;
+    Ok(row.map(|row| Claimed {
+        id: row.get("id"),
+        kind: row.get("kind"),
+        payload: row.get("payload"),
+        attempts: row.get("attempts"),
+        max_attempts: row.get("max_attempts"),
+    }))
+}
+
+pub async fn done(client: &Client, id: i64) -> Result<(), tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("update/job/done")).await?;
+    client.execute(&stmnt, &[&id]).await?;
+    Ok(())
+}
+
+/// Queues the job again after `retry_in` or marks it failed once its attempts are used up.
+pub async fn failed(
+    client: &Client,
+    id: i64,
+    error: &str,
+    retry_in: Duration,
+) -> Result<(), tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("update/job/failed")).await?;
+    client
+        .execute(&stmnt, &[&id, &error, &retry_in.as_secs_f64()])
+        .await?;
+    Ok(())
+}
+
+/// Queues a failed job with fresh attempts, `None` if it does not exist or has not failed.
+pub async fn retry(client: &Client, id: i64) -> Result<Option<QueuedJob>, tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("update/job/retry")).await?;
+    let row = client.query_opt(&stmnt, &[&id]).await?;
+    Ok(row.and_then(|row| QueuedJob::from_row(row).ok()))
+}
+
+pub async fn all(
+    client: &Client,
+    status: Option<JobStatus>,
+    position: &Position,
+) -> Result<Page<QueuedJob>, tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("get/job/all")).await?;
+    let rows = client.query(&stmnt, &position.params(&[&status])).await?;
+    Ok(position.page(rows).rows_into())
+}
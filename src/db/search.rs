@@ -0,0 +1,371 @@
+//! Builds the feed search query out of the optional filters of `/feeds/search`,
+//! so every combination runs as one parameterised statement.
+use tokio_postgres::types::ToSql;
+
+const COLUMNS: &str = r#"
+    f.id, f.title,
+    f.description, f.subtitle,
+    f.url, img.link as img,
+    f.link_web, author.name as author_name,
+    feed_language.name as language, f.submitted,
+    img.filename as img_cache,
+    img.blurhash as img_blurhash, img.color as img_color,
+    count(*) OVER() as total"#;
+
+const FROM: &str = r#"
+    feed f JOIN author ON author.id = f.author_id
+           JOIN feed_language ON feed_language.id = f.language
+           JOIN img ON f.img_id = img.id
+           LEFT JOIN search_queries($1) q ON q.config = f.ts_config"#;
+
+// feeds are matched with the text search configuration of their language,
+// author names with `simple`; trigram similarity only takes part in the ranking.
+// Each branch compares against a constant query, so they all run on an index.
+const TERM: &str = r#"f.id IN (
+            SELECT id FROM feed WHERE title = $1
+            UNION
+            SELECT feed.id
+            FROM feed JOIN search_queries($1) q ON q.config = feed.ts_config
+            WHERE feed.search @@ q.query
+            UNION
+            SELECT feed.id
+            FROM feed JOIN author ON author.id = feed.author_id
+            WHERE websearch_to_tsquery('simple', $1)::text <> '' AND
+                author.search @@ to_tsquery('simple', websearch_to_tsquery('simple', $1)::text || ':*')
+        )"#;
+
+const RANK: &str = r#"coalesce(ts_rank(f.search, q.query), 0)
+        + ts_rank(author.search, to_tsquery('simple', websearch_to_tsquery('simple', $1)::text || ':*'))
+        + greatest(similarity(f.title, $1), similarity(author.name, $1))"#;
+
+const HEADLINE_QUERY: &str = "coalesce(q.query, ''::tsquery)";
+
+const LANGUAGE: &str = "feed_language.name = ";
+const CATEGORY: &str =
+    "EXISTS (SELECT 1 FROM feed_category fc WHERE fc.feed_id = f.id AND fc.category_id = ";
+const EXPLICIT: &str = "EXISTS (SELECT 1 FROM episode e WHERE e.feed_id = f.id AND e.explicit) = ";
+const AVG_DURATION: &str = "(SELECT avg(e.duration) FROM episode e WHERE e.feed_id = f.id)";
+const LAST_EPISODE: &str = "(SELECT max(e.published) FROM episode e WHERE e.feed_id = f.id)";
+// compared whole, `%` and `_` of the filter are no wildcards
+const AUTHOR: &str = "lower(author.name) = lower(";
+
+#[derive(Debug, Default, Clone)]
+pub struct FeedFilter {
+    pub language: Option<String>,
+    /// Matches a category as well as a subcategory id.
+    pub category: Option<i32>,
+    pub explicit: Option<bool>,
+    /// Bounds for the average episode duration in seconds.
+    pub min_duration: Option<i64>,
+    pub max_duration: Option<i64>,
+    /// Only feeds with an episode published in the last n days.
+    pub updated_within: Option<i32>,
+    pub author: Option<String>,
+}
+
+pub struct Query {
+    pub sql: String,
+    params: Vec<Box<dyn ToSql + Sync>>,
+}
+
+impl Query {
+    pub fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params
+            .iter()
+            .map(|param| param.as_ref() as &(dyn ToSql + Sync))
+            .collect()
+    }
+
+    fn bind<T: ToSql + Sync + 'static>(&mut self, value: T, cast: &str) -> String {
+        self.params.push(Box::new(value));
+        format!("${}::{}", self.params.len(), cast)
+    }
+}
+
+pub struct FeedSearch<'a> {
+    term: &'a str,
+    filter: &'a FeedFilter,
+    highlight: Option<&'a str>,
+}
+
+impl<'a> FeedSearch<'a> {
+    pub fn new(term: &'a str, filter: &'a FeedFilter) -> Self {
+        Self {
+            term,
+            filter,
+            highlight: None,
+        }
+    }
+
+    /// Adds `ts_headline` snippets of title, description and subtitle to every page row.
+    /// `options` holds at least the `StartSel` and `StopSel` markers.
+    pub fn highlight(mut self, options: Option<&'a str>) -> Self {
+        self.highlight = options;
+        self
+    }
+
+    /// One page of matching feeds ordered by rank, each row carrying the total hit count.
+    pub fn page(&self, limit: i64, offset: i64) -> Query {
+        let mut query = self.query();
+        let conditions = self.conditions(&mut query, self.filter);
+        let headlines = match self.highlight {
+            Some(options) => headlines(&query.bind(options.to_owned(), "text")),
+            None => String::new(),
+        };
+        let limit = query.bind(limit, "int8");
+        let offset = query.bind(offset, "int8");
+        query.sql = format!(
+            "SELECT {}{}\nFROM {}\nWHERE {}\nORDER BY {} DESC\nLIMIT {} OFFSET {}",
+            COLUMNS, headlines, FROM, conditions, RANK, limit, offset
+        );
+        query
+    }
+
+    /// Number of matching feeds, for pages past the last hit that carry no `total`.
+    pub fn count(&self) -> Query {
+        let mut query = self.query();
+        let conditions = self.conditions(&mut query, self.filter);
+        query.sql = format!(
+            "SELECT count(*)::int8 as total\nFROM {}\nWHERE {}",
+            FROM, conditions
+        );
+        query
+    }
+
+    /// Hits per language with every filter applied except the language itself.
+    pub fn language_facet(&self) -> Query {
+        let filter = FeedFilter {
+            language: None,
+            ..self.filter.clone()
+        };
+        let mut query = self.query();
+        let conditions = self.conditions(&mut query, &filter);
+        query.sql = format!(
+            "SELECT feed_language.id, feed_language.name, count(*)::int8 as count\n\
+             FROM {}\nWHERE {}\n\
+             GROUP BY feed_language.id, feed_language.name\n\
+             ORDER BY count DESC, feed_language.name",
+            FROM, conditions
+        );
+        query
+    }
+
+    /// Hits per category with every filter applied except the category itself.
+    pub fn category_facet(&self) -> Query {
+        let filter = FeedFilter {
+            category: None,
+            ..self.filter.clone()
+        };
+        let mut query = self.query();
+        let conditions = self.conditions(&mut query, &filter);
+        query.sql = format!(
+            "SELECT c.id, c.description as name, count(*)::int8 as count\n\
+             FROM {}\n\
+                    JOIN feed_category fc ON f.id = fc.feed_id\n\
+                    JOIN category c ON c.id = fc.category_id\n\
+             WHERE {}\n\
+             GROUP BY c.id, c.description\n\
+             ORDER BY count DESC, c.description",
+            FROM, conditions
+        );
+        query
+    }
+
+    fn query(&self) -> Query {
+        Query {
+            sql: String::new(),
+            params: vec![Box::new(self.term.to_owned())],
+        }
+    }
+
+    fn conditions(&self, query: &mut Query, filter: &FeedFilter) -> String {
+        let mut conditions = vec![String::from("f.status = 'online'"), TERM.to_string()];
+
+        if let Some(language) = &filter.language {
+            conditions.push(format!(
+                "{}{}",
+                LANGUAGE,
+                query.bind(language.clone(), "text")
+            ));
+        }
+        if let Some(category) = filter.category {
+            conditions.push(format!("{}{})", CATEGORY, query.bind(category, "int4")));
+        }
+        if let Some(explicit) = filter.explicit {
+            conditions.push(format!("{}{}", EXPLICIT, query.bind(explicit, "bool")));
+        }
+        if let Some(min) = filter.min_duration {
+            conditions.push(format!("{} >= {}", AVG_DURATION, query.bind(min, "int8")));
+        }
+        if let Some(max) = filter.max_duration {
+            conditions.push(format!("{} <= {}", AVG_DURATION, query.bind(max, "int8")));
+        }
+        if let Some(days) = filter.updated_within {
+            conditions.push(format!(
+                "{} >= CURRENT_TIMESTAMP - make_interval(days => {})",
+                LAST_EPISODE,
+                query.bind(days, "int4")
+            ));
+        }
+        if let Some(author) = &filter.author {
+            conditions.push(format!("{}{})", AUTHOR, query.bind(author.clone(), "text")));
+        }
+
+        conditions.join("\n    AND ")
+    }
+}
+
+fn headlines(options: &str) -> String {
+    format!(
+        r#",
+    ts_headline(f.ts_config, f.title, {query}, {options} || ', HighlightAll=true') as hl_title,
+    ts_headline(f.ts_config, f.description, {query}, {options} || ', MaxFragments=2') as hl_description,
+    ts_headline(f.ts_config, f.subtitle, {query}, {options} || ', HighlightAll=true') as hl_subtitle"#,
+        query = HEADLINE_QUERY,
+        options = options
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FILTERS: u32 = 7;
+
+    fn filter_from_mask(mask: u32) -> FeedFilter {
+        let set = |bit: u32| mask & (1 << bit) != 0;
+        FeedFilter {
+            language: Some("de".to_string()).filter(|_| set(0)),
+            category: Some(3).filter(|_| set(1)),
+            explicit: Some(false).filter(|_| set(2)),
+            min_duration: Some(600).filter(|_| set(3)),
+            max_duration: Some(3600).filter(|_| set(4)),
+            updated_within: Some(30).filter(|_| set(5)),
+            author: Some("Chaos".to_string()).filter(|_| set(6)),
+        }
+    }
+
+    fn fragments() -> [String; FILTERS as usize] {
+        [
+            LANGUAGE.to_string(),
+            CATEGORY.to_string(),
+            EXPLICIT.to_string(),
+            format!("{} >=", AVG_DURATION),
+            format!("{} <=", AVG_DURATION),
+            LAST_EPISODE.to_string(),
+            AUTHOR.to_string(),
+        ]
+    }
+
+    // every placeholder $1..$n is used and n matches the bound params
+    fn assert_placeholders(query: &Query) {
+        let mut used = query
+            .sql
+            .split('$')
+            .skip(1)
+            .filter_map(|s| {
+                let digits = s
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>();
+                digits.parse::<usize>().ok()
+            })
+            .collect::<Vec<_>>();
+        used.sort_unstable();
+        used.dedup();
+        assert_eq!(
+            used,
+            (1..=query.params.len()).collect::<Vec<_>>(),
+            "{}",
+            query.sql
+        );
+    }
+
+    /// The param bound to the first placeholder after `fragment`, as its debug output.
+    fn param_after(query: &Query, fragment: &str) -> String {
+        let start = query.sql.find(fragment).expect(fragment) + fragment.len();
+        let placeholder = query.sql[start..]
+            .split('$')
+            .nth(1)
+            .and_then(|s| {
+                s.chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .parse::<usize>()
+                    .ok()
+            })
+            .expect(fragment);
+        format!("{:?}", query.params[placeholder - 1])
+    }
+
+    #[test]
+    fn test_every_filter_binds_its_own_value() {
+        let expected = ["\"de\"", "3", "false", "600", "3600", "30", "\"Chaos\""];
+        for mask in 0..(1 << FILTERS) {
+            let filter = filter_from_mask(mask);
+            let search = FeedSearch::new("podcast", &filter);
+            for query in &[search.page(20, 40), search.count()] {
+                assert_eq!(format!("{:?}", query.params[0]), "\"podcast\"");
+                for (bit, fragment) in fragments().iter().enumerate() {
+                    if mask & (1 << bit) != 0 {
+                        assert_eq!(
+                            param_after(query, fragment),
+                            expected[bit],
+                            "mask {:07b}\n{}",
+                            mask,
+                            query.sql
+                        );
+                    }
+                }
+            }
+            let page = search.page(20, 40);
+            assert_eq!(param_after(&page, "LIMIT "), "20");
+            assert_eq!(param_after(&page, "OFFSET "), "40");
+        }
+    }
+
+    #[test]
+    fn test_every_filter_combination() {
+        for mask in 0..(1 << FILTERS) {
+            let filter = filter_from_mask(mask);
+            let highlight = mask % 2 == 0;
+            let search = FeedSearch::new("podcast", &filter)
+                .highlight(Some(r#"StartSel="<b>", StopSel="</b>""#).filter(|_| highlight));
+            let page = search.page(20, 40);
+            assert_placeholders(&page);
+            assert_eq!(
+                page.params.len(),
+                3 + mask.count_ones() as usize + highlight as usize
+            );
+            assert_eq!(page.sql.contains("as hl_title"), highlight);
+
+            for (bit, fragment) in fragments().iter().enumerate() {
+                assert_eq!(
+                    page.sql.contains(fragment.as_str()),
+                    mask & (1 << bit) != 0,
+                    "mask {:07b} fragment {}",
+                    mask,
+                    fragment
+                );
+            }
+
+            assert_placeholders(&search.count());
+            assert_placeholders(&search.language_facet());
+            assert_placeholders(&search.category_facet());
+        }
+    }
+
+    #[test]
+    fn test_facets_ignore_own_dimension() {
+        let filter = filter_from_mask((1 << FILTERS) - 1);
+        let search = FeedSearch::new("podcast", &filter);
+
+        let language = search.language_facet();
+        assert!(!language.sql.contains(LANGUAGE));
+        assert!(language.sql.contains(CATEGORY));
+
+        let category = search.category_facet();
+        assert!(!category.sql.contains(CATEGORY));
+        assert!(category.sql.contains(LANGUAGE));
+    }
+}
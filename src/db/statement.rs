@@ -0,0 +1,154 @@
+//! Every `inc_sql!` statement, prepared once at boot so a broken query fails
+//! on startup instead of on the first request that uses it.
+use anyhow::Context;
+
+use crate::Client;
+
+macro_rules! statements {
+    ($($name:literal),* $(,)?) => {
+        &[$(($name, crate::inc_sql!($name))),*]
+    };
+}
+
+pub const ALL: &[(&str, &str)] = statements![
+    "delete/finished_submissions",
+    "delete/subscription",
+    "delete/unused_img",
+    "delete/webhook",
+    "get/account",
+    "get/account_is_moderator",
+    "get/author/all",
+    "get/category/all",
+    "get/category/by_feed_ids",
+    "get/category/by_id",
+    "get/category/by_name",
+    "get/category/exist_by_id",
+    "get/category/exist_by_name",
+    "get/category/get_id_by_feed_id",
+    "get/comment",
+    "get/completion",
+    "get/completion_fuzzy",
+    "get/episode",
+    "get/episode/search",
+    "get/episodes_for_feed_id",
+    "get/feed/artwork_refresh_due",
+    "get/feed/by_author_id",
+    "get/feed/by_author_name",
+    "get/feed/by_category_id",
+    "get/feed/by_category_name",
+    "get/feed/by_id",
+    "get/feed/charts",
+    "get/feed/duplicate/by_episode_guids",
+    "get/feed/duplicate/by_podcast_guid",
+    "get/feed/duplicate/by_url",
+    "get/feed/id_for_name",
+    "get/feed/img_link",
+    "get/feed/last_modified",
+    "get/feed/moderator/all_waiting_for_review",
+    "get/feed/moderator/by_id",
+    "get/feed/moderator/inbox",
+    "get/feed/moderator/reviewed",
+    "get/feed/recent",
+    "get/feed/related",
+    "get/feed/search/suggestions",
+    "get/feed/submitter_check",
+    "get/feed/user/submitted",
+    "get/feed/user/submitter_check",
+    "get/feed/user/subscription",
+    "get/feed/without_url_history",
+    "get/feed_image",
+    "get/img/without_placeholder",
+    "get/job/all",
+    "get/meta/count_author",
+    "get/meta/count_episode",
+    "get/meta/count_episode_duration",
+    "get/meta/count_feed",
+    "get/submission",
+    "get/submission_preview",
+    "get/tag/all",
+    "get/tag/episodes",
+    "get/tag/feeds",
+    "get/user_has_subscription",
+    "get/user_is_owner",
+    "get/webhook/all",
+    "get/webhook/deliveries",
+    "get/webhook/delivery",
+    "insert/account",
+    "insert/author",
+    "insert/categories",
+    "insert/comment",
+    "insert/episodes",
+    "insert/feed",
+    "insert/feed_category",
+    "insert/feed_url",
+    "insert/img",
+    "insert/job",
+    "insert/language",
+    "insert/submission",
+    "insert/subscription",
+    "insert/webhook",
+    "insert/webhook_deliveries",
+    "notify/catalogue",
+    "notify/moderator_feed",
+    "notify/submission",
+    "query_is_ok",
+    "update/assign_for_review",
+    "update/done_review",
+    "update/feed_img",
+    "update/img_placeholder",
+    "update/job/claim",
+    "update/job/done",
+    "update/job/failed",
+    "update/job/retry",
+    "update/review_feed",
+    "update/submission_feed",
+    "update/submission_progress",
+    "update/user_feed_visibility",
+    "update/webhook/delivery",
+];
+
+/// Prepares all statements on `client`, which also fills its statement cache.
+pub async fn prepare_all(client: &Client) -> Result<(), anyhow::Error> {
+    for (name, sql) in ALL {
+        client
+            .prepare_cached(sql)
+            .await
+            .with_context(|| format!("could not prepare sql/{}.sql", name))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sql_files(dir: &std::path::Path, names: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                if !path.ends_with("migrations") {
+                    sql_files(&path, names);
+                }
+            } else if path.extension().map_or(false, |ext| ext == "sql") {
+                let name = path.strip_prefix(concat!(env!("CARGO_MANIFEST_DIR"), "/sql/"));
+                names.push(name.unwrap().with_extension("").display().to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_sql_file_is_registered() {
+        let mut files = Vec::new();
+        sql_files(
+            std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/sql")),
+            &mut files,
+        );
+        files.sort();
+        let mut registered = ALL
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+        registered.sort();
+        assert_eq!(files, registered);
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Transaction;
+
+use crate::{inc_sql, Client};
+
+/// A stored feed submission, `progress` is a serialized `job::Progress`.
+#[derive(Debug)]
+pub struct Submission {
+    pub id: i64,
+    pub account_id: i32,
+    pub username: String,
+    pub feed_url: String,
+    pub progress: serde_json::Value,
+    pub feed_id: Option<i32>,
+    pub updated: DateTime<Utc>,
+}
+
+pub async fn insert(
+    client: &Client,
+    account_id: i32,
+    feed_url: &str,
+    progress: &serde_json::Value,
+    preview: Option<&[u8]>,
+) -> Result<(i64, DateTime<Utc>), tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("insert/submission")).await?;
+    let row = client
+        .query_one(&stmnt, &[&account_id, &feed_url, progress, &preview])
+        .await?;
+    Ok((row.get("id"), row.get("updated")))
+}
+
+pub async fn get(client: &Client, id: i64) -> Result<Option<Submission>, tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("get/submission")).await?;
+    let row = client.query_opt(&stmnt, &[&id]).await?;
+    Ok(row.map(|row| Submission {
+        id: row.get("id"),
+        account_id: row.get("account_id"),
+        username: row.get("username"),
+        feed_url: row.get("feed_url"),
+        progress: row.get("progress"),
+        feed_id: row.get("feed_id"),
+        updated: row.get("updated"),
+    }))
+}
+
+/// The previewed feed, until the feed is saved.
+pub async fn preview(client: &Client, id: i64) -> Result<Option<Vec<u8>>, tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/submission_preview"))
+        .await?;
+    let row = client.query_opt(&stmnt, &[&id]).await?;
+    Ok(row.and_then(|row| row.get("preview")))
+}
+
+/// Stores the progress and returns when it was updated.
+pub async fn update_progress(
+    client: &Client,
+    id: i64,
+    progress: &serde_json::Value,
+) -> Result<DateTime<Utc>, tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("update/submission_progress"))
+        .await?;
+    let row = client.query_one(&stmnt, &[&id, progress]).await?;
+    Ok(row.get("updated"))
+}
+
+/// Records the saved feed in the transaction that saves it and drops the preview.
+pub async fn set_feed(
+    trx: &Transaction<'_>,
+    id: i64,
+    feed_id: i32,
+) -> Result<(), tokio_postgres::Error> {
+    let stmnt = trx
+        .prepare_cached(inc_sql!("update/submission_feed"))
+        .await?;
+    trx.execute(&stmnt, &[&id, &feed_id]).await?;
+    Ok(())
+}
+
+/// Forgets submissions that finished more than `minutes` ago.
+pub async fn delete_finished(client: &Client, minutes: i32) -> Result<u64, tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("delete/finished_submissions"))
+        .await?;
+    client.execute(&stmnt, &[&minutes]).await
+}
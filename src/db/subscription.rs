@@ -9,7 +9,9 @@ pub async fn subscribe(
     user_id: i32,
     feed_id: i32,
 ) -> Result<(), tokio_postgres::Error> {
-    let stmnt = client.prepare(inc_sql!("insert/subscription")).await?;
+    let stmnt = client
+        .prepare_cached(inc_sql!("insert/subscription"))
+        .await?;
     let trx = client.transaction().await?;
     trx.execute(&stmnt, &[&user_id, &feed_id]).await?;
     trx.commit().await?;
@@ -21,7 +23,9 @@ pub async fn unsubscribe(
     user_id: i32,
     feed_id: i32,
 ) -> Result<(), tokio_postgres::Error> {
-    let stmnt = client.prepare(inc_sql!("delete/subscription")).await?;
+    let stmnt = client
+        .prepare_cached(inc_sql!("delete/subscription"))
+        .await?;
     let trx = client.transaction().await?;
     trx.execute(&stmnt, &[&user_id, &feed_id]).await?;
     trx.commit().await?;
@@ -34,9 +38,9 @@ pub async fn user_subscription_info(
     feed_id: i32,
 ) -> Result<FeedUserMeta, tokio_postgres::Error> {
     let stmnt_subs = client
-        .prepare(inc_sql!("get/user_has_subscription"))
+        .prepare_cached(inc_sql!("get/user_has_subscription"))
         .await?;
-    let stmnt_owner = client.prepare(inc_sql!("get/user_is_owner")).await?;
+    let stmnt_owner = client.prepare_cached(inc_sql!("get/user_is_owner")).await?;
     let sub_result = client.query_one(&stmnt_subs, &[&user_id, &feed_id]).await;
     let owner_result = client.query_one(&stmnt_owner, &[&user_id, &feed_id]).await;
 
@@ -26,9 +26,11 @@ impl DBConfig {
     }
 }
 
+pub type Connection = tokio_postgres::Connection<Socket, NoTlsStream>;
+
 struct DBContext {
     client: tokio_postgres::Client,
-    connection: tokio_postgres::Connection<Socket, NoTlsStream>,
+    connection: Connection,
     config: tokio_postgres::Config,
 }
 
@@ -49,6 +51,14 @@ async fn connect_with_conf() -> Result<DBContext, anyhow::Error> {
     })
 }
 
+/// A connection outside the pool, for sessions that have to stay open like `LISTEN`.
+pub async fn connect() -> Result<(tokio_postgres::Client, Connection), anyhow::Error> {
+    let DBContext {
+        client, connection, ..
+    } = connect_with_conf().await?;
+    Ok((client, connection))
+}
+
 pub async fn connect_and_migrate() -> Result<Pool, anyhow::Error> {
     let DBContext {
         mut client,
@@ -60,5 +70,9 @@ pub async fn connect_and_migrate() -> Result<Pool, anyhow::Error> {
         .run_async(&mut client)
         .await?;
     let mngr = Manager::new(config.clone(), tokio_postgres::NoTls);
-    Ok(Pool::new(mngr, 12))
+    let pool = Pool::new(mngr, 12);
+    let mut client = pool.get().await?;
+    super::statement::prepare_all(&client).await?;
+    super::duplicate::backfill_feed_urls(&mut client).await?;
+    Ok(pool)
 }
@@ -0,0 +1,104 @@
+use tokio_pg_mapper::FromTokioPostgresRow;
+
+use crate::{
+    inc_sql,
+    model::{
+        page::{Page, Position},
+        webhook::{Delivery, DeliveryStatus, Webhook},
+    },
+    Client,
+};
+
+/// What a worker needs to send one delivery.
+#[derive(Debug)]
+pub struct PendingDelivery {
+    pub id: i64,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub url: String,
+    pub secret: String,
+}
+
+pub async fn insert(
+    client: &Client,
+    url: &str,
+    secret: &str,
+    events: &[&str],
+) -> Result<Webhook, tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("insert/webhook")).await?;
+    let row = client.query_one(&stmnt, &[&url, &secret, &events]).await?;
+    Ok(Webhook::from_row(row).unwrap())
+}
+
+pub async fn all(client: &Client) -> Result<Vec<Webhook>, tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("get/webhook/all")).await?;
+    let rows = client.query(&stmnt, &[]).await?;
+    Ok(crate::db::rows_into_vec(rows))
+}
+
+/// `false` if there was no webhook with the id.
+pub async fn delete(client: &Client, id: i32) -> Result<bool, tokio_postgres::Error> {
+    let stmnt = client.prepare_cached(inc_sql!("delete/webhook")).await?;
+    Ok(client.execute(&stmnt, &[&id]).await? > 0)
+}
+
+/// Logs a pending delivery of `event` for every subscribed webhook and returns their ids.
+pub async fn insert_deliveries(
+    client: &Client,
+    event: &str,
+    payload: &serde_json::Value,
+) -> Result<Vec<i64>, tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("insert/webhook_deliveries"))
+        .await?;
+    let rows = client.query(&stmnt, &[&event, payload]).await?;
+    Ok(rows.iter().map(|row| row.get("id")).collect())
+}
+
+pub async fn delivery(
+    client: &Client,
+    id: i64,
+) -> Result<Option<PendingDelivery>, tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/webhook/delivery"))
+        .await?;
+    let row = client.query_opt(&stmnt, &[&id]).await?;
+    Ok(row.map(|row| PendingDelivery {
+        id: row.get("id"),
+        event: row.get("event"),
+        payload: row.get("payload"),
+        url: row.get("url"),
+        secret: row.get("secret"),
+    }))
+}
+
+/// Records the outcome of an attempt.
+pub async fn update_delivery(
+    client: &Client,
+    id: i64,
+    status: DeliveryStatus,
+    response_status: Option<i32>,
+    error: Option<&str>,
+) -> Result<(), tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("update/webhook/delivery"))
+        .await?;
+    client
+        .execute(&stmnt, &[&id, &status, &response_status, &error])
+        .await?;
+    Ok(())
+}
+
+pub async fn deliveries(
+    client: &Client,
+    webhook_id: i32,
+    position: &Position,
+) -> Result<Page<Delivery>, tokio_postgres::Error> {
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/webhook/deliveries"))
+        .await?;
+    let rows = client
+        .query(&stmnt, &position.params(&[&webhook_id]))
+        .await?;
+    Ok(position.page(rows).rows_into())
+}
@@ -70,7 +70,7 @@ pub async fn login(
     let form = form?.into_inner();
     validate_login_form(&form)?;
     let client = state.db_pool.get().await?;
-    let stmt = client.prepare(inc_sql!("get/account")).await?;
+    let stmt = client.prepare_cached(inc_sql!("get/account")).await?;
     let row = client
         .query_one(&stmt, &[&form.email])
         .await
@@ -1,37 +1,64 @@
+use crate::model::page::{Page, PageQuery};
 use crate::util::serialize;
-use crate::{db::rows_into_vec, inc_sql, model::Author, path::Path};
+use crate::{inc_sql, model::Author, path::Path};
 use crate::{model::feed::Feed, State};
 use actix_web::web;
-use futures_util::future;
 
 use super::{error::ApiError, ApiJsonResult};
 
-pub async fn all(state: web::Data<State>) -> ApiJsonResult<Vec<Author>> {
+const AUTHOR_PAGE_LIMIT: i64 = 100;
+const FEED_PAGE_LIMIT: i64 = 50;
+
+pub async fn all(
+    state: web::Data<State>,
+    page: web::Query<PageQuery>,
+) -> ApiJsonResult<Page<Author>> {
+    let key = page.cache_key();
+    if let Some(authors) = state.cache.authors.get(&key) {
+        return serialize(authors);
+    }
+    let position = page.position(AUTHOR_PAGE_LIMIT)?;
     let client = state.db_pool.get().await?;
 
-    let rows = client.query(inc_sql!("get/author/all"), &[]).await?;
-    let authors = rows_into_vec(rows);
+    let stmnt = client.prepare_cached(inc_sql!("get/author/all")).await?;
+    let rows = client.query(&stmnt, &position.params(&[])).await?;
+    let authors = position.page(rows).rows_into();
+    state.cache.authors.insert(key, Page::clone(&authors));
     serialize(authors)
 }
 
-pub async fn feeds(state: web::Data<State>, author_path: Path<String>) -> ApiJsonResult<Vec<Feed>> {
+pub async fn feeds(
+    state: web::Data<State>,
+    author_path: Path<String>,
+    page: web::Query<PageQuery>,
+) -> ApiJsonResult<Page<Feed>> {
+    let position = page.position(FEED_PAGE_LIMIT)?;
     let client = state.db_pool.get().await?;
     let author = author_path.decode();
     let rows = match author.parse::<i32>() {
         Ok(author_id) => {
-            let stmnt = client.prepare(inc_sql!("get/feed/by_author_id")).await?;
-            client.query(&stmnt, &[&author_id]).await?
+            let stmnt = client
+                .prepare_cached(inc_sql!("get/feed/by_author_id"))
+                .await?;
+            client
+                .query(&stmnt, &position.params(&[&author_id]))
+                .await?
         }
         Err(_) => {
-            let stmnt = client.prepare(inc_sql!("get/feed/by_author_name")).await?;
-            client.query(&stmnt, &[&author]).await?
+            let stmnt = client
+                .prepare_cached(inc_sql!("get/feed/by_author_name"))
+                .await?;
+            client.query(&stmnt, &position.params(&[&author])).await?
         }
     };
 
-    if rows.is_empty() {
+    if rows.is_empty() && position.is_first() {
         return Err(ApiError::AuthorNotFound(author));
     }
-    let feeds =
-        future::try_join_all(rows.into_iter().map(|row| Feed::from(&client, row, None))).await?;
-    serialize(feeds)
+    let Page { items, next_cursor } = position.page(rows);
+    let feeds = Feed::from_rows(&client, items).await?;
+    serialize(Page {
+        items: feeds,
+        next_cursor,
+    })
 }
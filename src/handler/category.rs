@@ -1,21 +1,28 @@
 use crate::model::category::Category;
 use crate::path;
-use crate::util::serialize;
 use crate::{db, State};
 use actix_web::web;
 
-use super::ApiJsonResult;
+use super::{conditional::Conditional, error::ApiError};
 
-pub async fn all(state: web::Data<State>) -> ApiJsonResult<Vec<Category>> {
+pub async fn all(state: web::Data<State>) -> Result<Conditional<Vec<Category>>, ApiError> {
+    if let Some(categories) = state.cache.categories.get("") {
+        return Ok(Conditional::new(categories));
+    }
     let client = state.db_pool.get().await?;
-    serialize(db::category::get_all(&client).await?)
+    let categories = db::category::get_all(&client).await?;
+    state
+        .cache
+        .categories
+        .insert(String::new(), categories.clone());
+    Ok(Conditional::new(categories))
 }
 
 pub async fn by_id_or_name(
     state: web::Data<State>,
     path: path::Path<String>,
-) -> ApiJsonResult<Category> {
+) -> Result<Conditional<Category>, ApiError> {
     let client = state.db_pool.get().await?;
     let catagories = db::category::get_by_id_or_name(&client, &path.decode()).await?;
-    serialize(catagories)
+    Ok(Conditional::new(catagories))
 }
@@ -3,10 +3,16 @@ use actix_web::{
     HttpResponse,
 };
 
-use crate::{db, model::NewComment};
+use crate::{
+    db,
+    model::{page::PageQuery, webhook::Event, NewComment},
+    webhook,
+};
 
 use super::error::ApiError;
 
+const COMMENT_PAGE_LIMIT: i64 = 50;
+
 // valid if content is not empty
 pub async fn new(
     state: web::Data<crate::State>,
@@ -16,6 +22,7 @@ pub async fn new(
     let mut client = state.db_pool.get().await?;
 
     let new_comment = db::comment::insert(&mut client, comment_json.into_inner()).await?;
+    webhook::emit(&client, Event::CommentCreated, &new_comment).await;
 
     Ok(HttpResponse::Ok().json(&new_comment))
 }
@@ -23,9 +30,11 @@ pub async fn new(
 pub async fn get_for_feed(
     state: web::Data<crate::State>,
     feed_id: web::Path<i32>,
+    page: web::Query<PageQuery>,
 ) -> Result<HttpResponse, ApiError> {
+    let position = page.position(COMMENT_PAGE_LIMIT)?;
     let client = state.db_pool.get().await?;
-    let comments = db::comment::get(&client, feed_id.into_inner()).await?;
+    let comments = db::comment::get(&client, feed_id.into_inner(), &position).await?;
 
     Ok(HttpResponse::Ok().json(comments))
 }
@@ -0,0 +1,118 @@
+//! Conditional GET for catalogue responses: a weak ETag over the JSON body and an
+//! optional `Last-Modified`, a still fresh client copy is answered with 304.
+use actix_web::{
+    http::{header, StatusCode},
+    HttpRequest, HttpResponse, HttpResponseBuilder, Responder,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::time_date::{format_http_date, parse_datetime_rfc822};
+
+pub struct Conditional<T> {
+    body: Option<T>,
+    last_modified: Option<DateTime<Utc>>,
+    vary_cookie: bool,
+}
+
+impl<T> Conditional<T> {
+    pub fn new(body: T) -> Self {
+        Self {
+            body: Some(body),
+            last_modified: None,
+            vary_cookie: false,
+        }
+    }
+
+    pub fn last_modified(mut self, last_modified: Option<DateTime<Utc>>) -> Self {
+        self.last_modified = last_modified;
+        self
+    }
+
+    /// The body depends on the session, shared caches must not hand it to other users.
+    pub fn vary_cookie(mut self) -> Self {
+        self.vary_cookie = true;
+        self
+    }
+
+    /// 304 without loading the body, see [`not_modified_since`].
+    pub fn not_modified(last_modified: DateTime<Utc>) -> Self {
+        Self {
+            body: None,
+            last_modified: Some(last_modified),
+            vary_cookie: false,
+        }
+    }
+}
+
+/// The request revalidates by date only and its copy is not older than `last_modified`.
+pub fn not_modified_since(req: &HttpRequest, last_modified: &DateTime<Utc>) -> bool {
+    // If-None-Match takes precedence over If-Modified-Since
+    if req.headers().contains_key(header::IF_NONE_MATCH) {
+        return false;
+    }
+    req.headers()
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_datetime_rfc822(value).ok())
+        .map_or(false, |since| {
+            last_modified.timestamp() <= since.timestamp()
+        })
+}
+
+fn etag(body: &[u8]) -> String {
+    format!("W/\"{}\"", &blake3::hash(body).to_hex()[..32])
+}
+
+// weak comparison, the W/ prefix is ignored on both sides
+fn etag_matches(req: &HttpRequest, etag: &str) -> bool {
+    let tag = etag.trim_start_matches("W/");
+    req.headers()
+        .get_all(header::IF_NONE_MATCH)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == tag)
+}
+
+impl<T: Serialize> Responder for Conditional<T> {
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse {
+        let last_modified = self.last_modified.as_ref().map(format_http_date);
+        let body = match self.body.as_ref().map(serde_json::to_vec) {
+            Some(Ok(body)) => Some(body),
+            Some(Err(err)) => {
+                log::error!("{:?}", err);
+                return HttpResponse::InternalServerError().finish();
+            }
+            None => None,
+        };
+        let etag = body.as_deref().map(etag);
+
+        let fresh = match (&etag, &self.last_modified) {
+            (None, _) => true,
+            (Some(etag), _) if etag_matches(req, etag) => true,
+            (_, Some(date)) => not_modified_since(req, date),
+            _ => false,
+        };
+        let mut res = HttpResponseBuilder::new(if fresh {
+            StatusCode::NOT_MODIFIED
+        } else {
+            StatusCode::OK
+        });
+        res.insert_header((header::CACHE_CONTROL, "no-cache"));
+        if self.vary_cookie {
+            res.insert_header((header::VARY, "Cookie"));
+        }
+        if let Some(etag) = etag {
+            res.insert_header((header::ETAG, etag));
+        }
+        if let Some(last_modified) = last_modified {
+            res.insert_header((header::LAST_MODIFIED, last_modified));
+        }
+
+        match body {
+            Some(body) if !fresh => res.content_type("application/json").body(body),
+            _ => res.finish(),
+        }
+    }
+}
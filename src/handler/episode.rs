@@ -1,57 +1,99 @@
-use super::{error::ApiError, ApiJsonResult};
-use crate::db;
+use super::{conditional::Conditional, error::ApiError, feed::highlight_options, ApiJsonResult};
 use crate::inc_sql;
+use crate::model::page::{Page, PageQuery};
 use crate::model::preview::episode::Episode;
-use crate::model::preview::episode::EpisodeNext;
-use crate::util::serialize;
+use crate::model::search::EpisodeHit;
+use crate::util::{percent_decode, serialize};
 use crate::State;
 use actix_web::web;
+use chrono::{DateTime, Utc};
 
-#[derive(Debug, serde::Deserialize)]
-pub struct QueryOffset {
-    offset: Option<i64>,
-    limit: Option<i64>,
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeSearchQuery {
+    term: String,
+    feed: Option<i32>,
+    lang: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    highlight: Option<bool>,
+    start_sel: Option<String>,
+    stop_sel: Option<String>,
+}
+
+pub const EPISODE_PAGE_LIMIT: i64 = 50;
+
+pub async fn search(
+    query: Result<web::Query<EpisodeSearchQuery>, actix_web::Error>,
+    page: web::Query<PageQuery>,
+    state: web::Data<State>,
+) -> ApiJsonResult<Page<EpisodeHit>> {
+    let query = query.map_err(|_e| ApiError::MissingTerm)?;
+    let position = page.position(EPISODE_PAGE_LIMIT)?;
+    let search_term = percent_decode(&query.term);
+    let highlight = highlight_options(
+        query.highlight,
+        query.start_sel.as_deref(),
+        query.stop_sel.as_deref(),
+    )?;
+    let client = state.db_pool.get().await?;
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/episode/search"))
+        .await?;
+    let rows = client
+        .query(
+            &stmnt,
+            &position.params(&[
+                &search_term,
+                &query.feed,
+                &query.lang,
+                &query.from,
+                &query.to,
+                &highlight,
+            ]),
+        )
+        .await?;
+
+    serialize(position.page(rows).map(EpisodeHit::from))
 }
 
 pub async fn by_feed_id(
     state: web::Data<State>,
     id: web::Path<i32>,
-    query: web::Query<QueryOffset>,
-) -> ApiJsonResult<EpisodeNext> {
-    let offset = query.offset.unwrap_or(-1);
-    let limit = query.limit.unwrap_or(50);
+    page: web::Query<PageQuery>,
+) -> Result<Conditional<Page<Episode>>, ApiError> {
+    let position = page.position(EPISODE_PAGE_LIMIT)?;
     let client = state.db_pool.get().await?;
     let feed_id = id.into_inner();
 
-    let episodes_stmnt = client.prepare(inc_sql!("get/episodes_for_feed_id")).await?;
+    let episodes_stmnt = client
+        .prepare_cached(inc_sql!("get/episodes_for_feed_id"))
+        .await?;
     let episode_rows = client
-        .query(&episodes_stmnt, &[&feed_id, &offset, &limit])
+        .query(&episodes_stmnt, &position.params(&[&feed_id]))
         .await?;
 
-    if episode_rows.is_empty() {
+    if episode_rows.is_empty() && position.is_first() {
         return Err(ApiError::FeedByIdNotFound(feed_id));
     }
 
-    let episodes = episode_rows
-        .into_iter()
-        .map(|row| Episode::from(row))
-        .collect::<Vec<_>>();
-
-    serialize(EpisodeNext {
-        offset: db::episode::episode_offset(&client, &episodes, &feed_id).await?,
-        items: episodes,
-    })
+    Ok(Conditional::new(
+        position.page(episode_rows).map(Episode::from),
+    ))
 }
 
-pub async fn by_episode_id(state: web::Data<State>, id: web::Path<i64>) -> ApiJsonResult<Episode> {
+pub async fn by_episode_id(
+    state: web::Data<State>,
+    id: web::Path<i64>,
+) -> Result<Conditional<Episode>, ApiError> {
     let client = state.db_pool.get().await?;
     let id = id.into_inner();
-    let stmnt = client.prepare(inc_sql!("get/episode")).await?;
+    let stmnt = client.prepare_cached(inc_sql!("get/episode")).await?;
 
     let row = client
         .query_one(&stmnt, &[&id])
         .await
         .map_err(|_err| ApiError::EpisodeNotFound(id))?;
 
-    serialize(Episode::from(row))
+    Ok(Conditional::new(Episode::from(row)))
 }
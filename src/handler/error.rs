@@ -20,12 +20,26 @@ pub enum ApiError {
     FeedByNameNotFound(String),
     #[error("author {0} was not found or has currently no online episodes")]
     AuthorNotFound(String),
+    #[error("tag {0} was not found")]
+    TagNotFound(String),
     #[error("missing field `term`")]
     MissingTerm,
+    #[error("invalid cursor `{0}`")]
+    InvalidCursor(String),
+    #[error("invalid highlight marker `{0}`")]
+    InvalidHighlightMarker(String),
     #[error("{0}")]
     BadRequest(#[from] actix_web::Error),
     #[error("episode id: {0} not found")]
     EpisodeNotFound(i64),
+    #[error("job {0} was not found")]
+    JobNotFound(i64),
+    #[error("failed job {0} was not found")]
+    FailedJobNotFound(i64),
+    #[error("webhook {0} was not found")]
+    WebhookNotFound(i32),
+    #[error("invalid webhook: {0}")]
+    InvalidWebhook(&'static str),
     #[error("unauthorized access")]
     Unauthorized,
     #[error("User has no permission to access the moderator site")]
@@ -33,12 +47,16 @@ pub enum ApiError {
 }
 generic_handler_err!(ApiError, ApiError::Internal);
 
-#[derive(Debug, serde::Serialize, Clone)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct JsonError {
     message: String,
     #[serde(with = "http_serde::status_code")]
+    #[schemars(with = "u16")]
     status_code: StatusCode,
+    /// The existing feed a rejected submission duplicates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    feed_id: Option<i32>,
 }
 
 impl JsonError {
@@ -46,8 +64,15 @@ impl JsonError {
         Self {
             message,
             status_code,
+            feed_id: None,
         }
     }
+
+    pub fn feed_id(mut self, feed_id: i32) -> Self {
+        self.feed_id = Some(feed_id);
+        self
+    }
+
     pub fn into_response(&self) -> HttpResponse {
         HttpResponseBuilder::new(self.status_code).json(self.clone())
     }
@@ -68,10 +93,7 @@ impl Display for JsonError {
 
 pub fn not_found() -> HttpResponse {
     let status_code = StatusCode::NOT_FOUND;
-    let body = JsonError {
-        message: String::from("resource does not exist"),
-        status_code,
-    };
+    let body = JsonError::new(String::from("resource does not exist"), status_code);
 
     HttpResponseBuilder::new(status_code).json(body)
 }
@@ -86,8 +108,16 @@ impl actix_web::ResponseError for ApiError {
             | ApiError::FeedByIdNotFound(_)
             | ApiError::FeedByNameNotFound(_)
             | ApiError::EpisodeNotFound(_)
-            | ApiError::AuthorNotFound(_) => StatusCode::NOT_FOUND,
-            ApiError::BadRequest(_) | ApiError::MissingTerm => StatusCode::BAD_REQUEST,
+            | ApiError::JobNotFound(_)
+            | ApiError::FailedJobNotFound(_)
+            | ApiError::WebhookNotFound(_)
+            | ApiError::AuthorNotFound(_)
+            | ApiError::TagNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_)
+            | ApiError::MissingTerm
+            | ApiError::InvalidCursor(_)
+            | ApiError::InvalidHighlightMarker(_)
+            | ApiError::InvalidWebhook(_) => StatusCode::BAD_REQUEST,
         }
     }
 
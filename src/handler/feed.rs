@@ -1,99 +1,220 @@
 use crate::{
-    db::rows_into_vec,
+    db::{
+        rows_into_vec,
+        search::{FeedFilter, FeedSearch},
+    },
     inc_sql,
     model::{
         feed::{Feed, TinyFeed},
+        page::{Page, PageQuery},
         preview::episode::Episode,
+        search::{Facets, SearchPage},
         user::Account,
-        Completion, Permission,
+        Permission, Suggestion,
     },
-    util::{percent_decode, serialize},
+    util::{escape_like, percent_decode, serialize},
 };
 use crate::{path::Path, State};
 use actix_session::Session;
-use actix_web::web;
+use actix_web::{web, HttpRequest};
 
 use futures_util::future;
 
-use super::{error::ApiError, ApiJsonResult};
+use super::{
+    conditional::{not_modified_since, Conditional},
+    episode::EPISODE_PAGE_LIMIT,
+    error::ApiError,
+    ApiJsonResult,
+};
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct SearchQuery {
     term: String,
     lang: Option<String>,
     category: Option<i32>,
+    explicit: Option<bool>,
+    min_duration: Option<i64>,
+    max_duration: Option<i64>,
+    updated_within: Option<i32>,
+    author: Option<String>,
+    offset: Option<i64>,
+    limit: Option<i64>,
+    highlight: Option<bool>,
+    start_sel: Option<String>,
+    stop_sel: Option<String>,
+}
+
+impl SearchQuery {
+    fn filter(&self) -> FeedFilter {
+        FeedFilter {
+            language: self.lang.clone(),
+            category: self.category,
+            explicit: self.explicit,
+            min_duration: self.min_duration,
+            max_duration: self.max_duration,
+            updated_within: self.updated_within,
+            author: self.author.as_deref().map(percent_decode),
+        }
+    }
+}
+
+const SEARCH_LIMIT_DEFAULT: i64 = 50;
+const SEARCH_LIMIT_MAX: i64 = 100;
+
+/// Builds the `ts_headline` options if highlighting was requested.
+/// Markers may not contain `"` since they are quoted inside the options.
+pub(super) fn highlight_options(
+    highlight: Option<bool>,
+    start_sel: Option<&str>,
+    stop_sel: Option<&str>,
+) -> Result<Option<String>, ApiError> {
+    if highlight != Some(true) {
+        return Ok(None);
+    }
+    let start_sel = start_sel.unwrap_or("<mark>");
+    let stop_sel = stop_sel.unwrap_or("</mark>");
+    for marker in &[start_sel, stop_sel] {
+        if marker.is_empty() || marker.len() > 32 || marker.contains('"') {
+            return Err(ApiError::InvalidHighlightMarker(marker.to_string()));
+        }
+    }
+    Ok(Some(format!(
+        r#"StartSel="{}", StopSel="{}""#,
+        start_sel, stop_sel
+    )))
 }
 
-pub async fn charts(state: web::Data<State>) -> ApiJsonResult<Vec<TinyFeed>> {
+const CHARTS_PAGE_LIMIT: i64 = 20;
+const FEED_PAGE_LIMIT: i64 = 50;
+
+pub async fn charts(
+    state: web::Data<State>,
+    page: web::Query<PageQuery>,
+) -> ApiJsonResult<Page<TinyFeed>> {
+    let key = page.cache_key();
+    if let Some(feeds) = state.cache.charts.get(&key) {
+        return serialize(feeds);
+    }
+    let position = page.position(CHARTS_PAGE_LIMIT)?;
     let client = state.db_pool.get().await?;
-    let rows = client.query(inc_sql!("get/feed/charts"), &[]).await?;
-    let feeds = rows_into_vec(rows);
+    let stmnt = client.prepare_cached(inc_sql!("get/feed/charts")).await?;
+    let rows = client.query(&stmnt, &position.params(&[])).await?;
+    let feeds = position.page(rows).rows_into();
+    state.cache.charts.insert(key, Page::clone(&feeds));
     serialize(feeds)
 }
 
-pub async fn recent(state: web::Data<State>) -> ApiJsonResult<Vec<TinyFeed>> {
+pub async fn recent(
+    state: web::Data<State>,
+    page: web::Query<PageQuery>,
+) -> ApiJsonResult<Page<TinyFeed>> {
+    let key = page.cache_key();
+    if let Some(feeds) = state.cache.recent.get(&key) {
+        return serialize(feeds);
+    }
+    let position = page.position(CHARTS_PAGE_LIMIT)?;
     let client = state.db_pool.get().await?;
-    let rows = client.query(inc_sql!("get/feed/recent"), &[]).await?;
-    let feeds = rows_into_vec(rows);
+    let stmnt = client.prepare_cached(inc_sql!("get/feed/recent")).await?;
+    let rows = client.query(&stmnt, &position.params(&[])).await?;
+    let feeds = position.page(rows).rows_into();
+    state.cache.recent.insert(key, Page::clone(&feeds));
     serialize(feeds)
 }
 
 pub async fn search(
     query: Result<web::Query<SearchQuery>, actix_web::Error>,
     state: web::Data<State>,
-) -> ApiJsonResult<Vec<Feed>> {
+) -> ApiJsonResult<SearchPage> {
     let client = state.db_pool.get().await?;
     let query = query.map_err(|_e| ApiError::MissingTerm)?;
     let search_term = percent_decode(&query.term);
-    let feeds_row = match &query.lang {
-        Some(lang) => {
-            match &query.category {
-                Some(category) => {
-                    let feed_stmnt = client
-                        .prepare(inc_sql!("get/feed/search/with_language_category"))
-                        .await?;
-                    client
-                        .query(&feed_stmnt, &[&search_term, lang, category])
-                        .await?
-                }
-                None => {
-                    let feed_stmnt = client
-                        .prepare(inc_sql!("get/feed/search/with_language"))
-                        .await?;
-                    client.query(&feed_stmnt, &[&search_term, lang]).await?
-                }
-            };
-            let feed_stmnt = client
-                .prepare(inc_sql!("get/feed/search/with_language"))
-                .await?;
-            client.query(&feed_stmnt, &[&search_term, lang]).await?
-        }
-        None => {
-            let feed_stmnt = client.prepare(inc_sql!("get/feed/search/all")).await?;
-            client.query(&feed_stmnt, &[&search_term]).await?
+    let offset = query.offset.unwrap_or(0).max(0);
+    let limit = query
+        .limit
+        .unwrap_or(SEARCH_LIMIT_DEFAULT)
+        .clamp(1, SEARCH_LIMIT_MAX);
+    let filter = query.filter();
+    let highlight = highlight_options(
+        query.highlight,
+        query.start_sel.as_deref(),
+        query.stop_sel.as_deref(),
+    )?;
+    let search = FeedSearch::new(&search_term, &filter).highlight(highlight.as_deref());
+    let page = search.page(limit, offset);
+    let language_facet = search.language_facet();
+    let category_facet = search.category_facet();
+    // the SQL only varies with the set filters, so the few variants stay prepared
+    let (stmnt_page, stmnt_language, stmnt_category) = future::try_join3(
+        client.prepare_cached(&page.sql),
+        client.prepare_cached(&language_facet.sql),
+        client.prepare_cached(&category_facet.sql),
+    )
+    .await?;
+    let (feeds_row, language_rows, category_rows) = future::try_join3(
+        client.query(&stmnt_page, &page.params()),
+        client.query(&stmnt_language, &language_facet.params()),
+        client.query(&stmnt_category, &category_facet.params()),
+    )
+    .await?;
+    let total = match feeds_row.first() {
+        Some(row) => row.get::<_, i64>("total"),
+        None if offset > 0 => {
+            let count = search.count();
+            let stmnt = client.prepare_cached(&count.sql).await?;
+            client
+                .query_one(&stmnt, &count.params())
+                .await?
+                .get("total")
         }
+        None => 0,
     };
-    let feeds = future::try_join_all(
-        feeds_row
+
+    let did_you_mean = if total == 0 {
+        let stmnt = client
+            .prepare_cached(inc_sql!("get/feed/search/suggestions"))
+            .await?;
+        client
+            .query(&stmnt, &[&search_term])
+            .await?
             .into_iter()
-            .map(|row| Feed::from(&client, row, None)),
-    )
-    .await?;
-    serialize(feeds)
+            .map(|row| row.get("suggestion"))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let feeds = Feed::from_rows(&client, feeds_row).await?;
+    serialize(SearchPage {
+        items: feeds,
+        total,
+        offset,
+        limit,
+        facets: Facets {
+            languages: rows_into_vec(language_rows),
+            categories: rows_into_vec(category_rows),
+        },
+        did_you_mean,
+    })
 }
 
+/// A feed by id or by title. Titles are not unique, a title shared by several
+/// feeds resolves to the one saved first, the others are only reachable by id.
 pub async fn by_name_or_id(
+    req: HttpRequest,
     path: Path<String>,
     state: web::Data<State>,
     session: Session,
-) -> ApiJsonResult<Feed> {
+) -> Result<Conditional<Feed>, ApiError> {
     let client = state.db_pool.get().await?;
 
     let feed_id = match path.parse::<i32>() {
         Ok(id) => id,
         Err(_) => {
             let feed_name = path.decode();
-            let feed_id_stmnt = client.prepare(inc_sql!("get/feed/id_for_name")).await?;
+            let feed_id_stmnt = client
+                .prepare_cached(inc_sql!("get/feed/id_for_name"))
+                .await?;
             client
                 .query_one(&feed_id_stmnt, &[&feed_name])
                 .await
@@ -102,24 +223,42 @@ pub async fn by_name_or_id(
         }
     };
 
+    // only online feeds are dated, hidden ones never short-circuit the permission check
+    let last_modified_stmnt = client
+        .prepare_cached(inc_sql!("get/feed/last_modified"))
+        .await?;
+    let last_modified = client
+        .query_opt(&last_modified_stmnt, &[&feed_id])
+        .await?
+        .map(|row| row.get("last_modified"));
+    if let Some(last_modified) = last_modified {
+        if not_modified_since(&req, &last_modified) {
+            return Ok(Conditional::not_modified(last_modified).vary_cookie());
+        }
+    }
+
     let feed_stmnt = match Account::from_session(&session) {
         Some(account) if account.permission() == Permission::Admin => {
-            client.prepare(inc_sql!("get/feed/moderator/by_id")).await?
+            client
+                .prepare_cached(inc_sql!("get/feed/moderator/by_id"))
+                .await?
         }
         Some(account) => {
             let submitter_check_stmnt = client
-                .prepare(inc_sql!("get/feed/user/submitter_check"))
+                .prepare_cached(inc_sql!("get/feed/user/submitter_check"))
                 .await?;
             let x = client
                 .query_one(&submitter_check_stmnt, &[&feed_id, &account.id()])
                 .await;
             if x.is_ok() {
-                client.prepare(inc_sql!("get/feed/moderator/by_id")).await?
+                client
+                    .prepare_cached(inc_sql!("get/feed/moderator/by_id"))
+                    .await?
             } else {
-                client.prepare(inc_sql!("get/feed/by_id")).await?
+                client.prepare_cached(inc_sql!("get/feed/by_id")).await?
             }
         }
-        _ => client.prepare(inc_sql!("get/feed/by_id")).await?,
+        _ => client.prepare_cached(inc_sql!("get/feed/by_id")).await?,
     };
 
     let feed_row = client
@@ -127,32 +266,36 @@ pub async fn by_name_or_id(
         .await
         .map_err(|_e| ApiError::FeedByIdNotFound(feed_id))?;
 
-    let episodes_stmnt = client.prepare(inc_sql!("get/episodes_for_feed_id")).await?;
-    let offset: i64 = 0;
-    let limit: i64 = 50;
+    let episodes_stmnt = client
+        .prepare_cached(inc_sql!("get/episodes_for_feed_id"))
+        .await?;
+    let position = PageQuery::default().position(EPISODE_PAGE_LIMIT)?;
     let episode_rows = client
-        .query(&episodes_stmnt, &[&feed_id, &offset, &limit])
+        .query(&episodes_stmnt, &position.params(&[&feed_id]))
         .await?;
-    let episodes = episode_rows
-        .into_iter()
-        .map(|row| Episode::from(row))
-        .collect::<Vec<_>>();
+    let episodes = position.page(episode_rows).map(Episode::from);
 
     let feed = Feed::from(&client, feed_row, Some(episodes)).await?;
-    serialize(feed)
+    Ok(Conditional::new(feed)
+        .last_modified(last_modified)
+        .vary_cookie())
 }
 
 pub async fn completion(
     path: Path<String>,
     state: web::Data<State>,
-) -> ApiJsonResult<Vec<Completion>> {
+) -> ApiJsonResult<Vec<Suggestion>> {
     let name = &path.decode();
     if name.trim().is_empty() {
         return serialize(vec![]);
     }
+    let prefix = name.trim().to_lowercase();
+    if let Some(suggestions) = state.cache.completions.get(&prefix) {
+        return serialize(suggestions);
+    }
 
     let client = state.db_pool.get().await?;
-    let query_is_ok_stmnt = client.prepare(inc_sql!("query_is_ok")).await?;
+    let query_is_ok_stmnt = client.prepare_cached(inc_sql!("query_is_ok")).await?;
     let code: i32 = client
         .query_one(&query_is_ok_stmnt, &[&name])
         .await?
@@ -161,47 +304,71 @@ pub async fn completion(
     if code == 0 {
         return serialize(vec![]);
     }
-    let stmnt = client.prepare(inc_sql!("get/completion")).await?;
-    let rows = client.query(&stmnt, &[&name]).await?;
-    let completions = rows_into_vec(rows);
-    serialize(completions)
+    let stmnt = client.prepare_cached(inc_sql!("get/completion")).await?;
+    let mut rows = client
+        .query(&stmnt, &[&name, &escape_like(name.trim())])
+        .await?;
+    if rows.is_empty() {
+        let fuzzy_stmnt = client
+            .prepare_cached(inc_sql!("get/completion_fuzzy"))
+            .await?;
+        rows = client.query(&fuzzy_stmnt, &[&name]).await?;
+    }
+    let suggestions = rows.into_iter().map(Suggestion::from).collect::<Vec<_>>();
+    state.cache.completions.insert(prefix, suggestions.clone());
+    serialize(suggestions)
 }
 
 pub async fn by_category(
     state: web::Data<State>,
     category: Path<String>,
-) -> ApiJsonResult<Vec<Feed>> {
+    page: web::Query<PageQuery>,
+) -> Result<Conditional<Page<Feed>>, ApiError> {
+    let position = page.position(FEED_PAGE_LIMIT)?;
     let client = state.db_pool.get().await?;
     let rows = if let Ok(category_id) = category.parse::<i32>() {
-        let stmnt_exists = client.prepare(inc_sql!("get/category/exist_by_id")).await?;
+        let stmnt_exists = client
+            .prepare_cached(inc_sql!("get/category/exist_by_id"))
+            .await?;
         client
             .query_one(&stmnt_exists, &[&category_id])
             .await
             .map_err(|_e| ApiError::CategoryNotFound(category.clone()))?;
 
-        let stmnt = client.prepare(inc_sql!("get/feed/by_category_id")).await?;
-        client.query(&stmnt, &[&category_id]).await?
+        let stmnt = client
+            .prepare_cached(inc_sql!("get/feed/by_category_id"))
+            .await?;
+        client
+            .query(&stmnt, &position.params(&[&category_id]))
+            .await?
     } else {
         let category_name = &category.decode();
         let stmnt_exists = client
-            .prepare(inc_sql!("get/category/exist_by_name"))
+            .prepare_cached(inc_sql!("get/category/exist_by_name"))
             .await?;
         client
             .query_one(&stmnt_exists, &[&category_name])
             .await
             .map_err(|_e| ApiError::CategoryNotFound(category.clone()))?;
         let stmnt_feeds = client
-            .prepare(inc_sql!("get/feed/by_category_name"))
+            .prepare_cached(inc_sql!("get/feed/by_category_name"))
             .await?;
-        client.query(&stmnt_feeds, &[category_name]).await?
+        client
+            .query(&stmnt_feeds, &position.params(&[category_name]))
+            .await?
     };
 
-    let feeds =
-        future::try_join_all(rows.into_iter().map(|row| Feed::from(&client, row, None))).await?;
+    let Page { items, next_cursor } = position.page(rows);
+    let feeds = Feed::from_rows(&client, items).await?;
 
-    serialize(feeds)
+    Ok(Conditional::new(Page {
+        items: feeds,
+        next_cursor,
+    }))
 }
 
+/// Five random feeds of the same category. A random sample has no order a cursor
+/// could continue, so unlike the other lists it is returned without a page.
 pub async fn related(
     state: web::Data<State>,
     feed_id: Result<actix_web::web::Path<i32>, actix_web::Error>,
@@ -213,14 +380,14 @@ pub async fn related(
     let client = state.db_pool.get().await?;
 
     let stmnt_category_id = client
-        .prepare(inc_sql!("get/category/get_id_by_feed_id"))
+        .prepare_cached(inc_sql!("get/category/get_id_by_feed_id"))
         .await?;
     let category_id = client
         .query_one(&stmnt_category_id, &[&feed_id])
         .await?
         .get::<_, i32>("category_id");
 
-    let stmnt_feeds = client.prepare(inc_sql!("get/feed/related")).await?;
+    let stmnt_feeds = client.prepare_cached(inc_sql!("get/feed/related")).await?;
     let rows = client
         .query(&stmnt_feeds, &[&category_id, &feed_id])
         .await?;
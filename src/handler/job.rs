@@ -0,0 +1,36 @@
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use anyhow::anyhow;
+
+use super::{error::ApiError, ApiJsonResult};
+use crate::{
+    job::{self, Job},
+    model::user::Account,
+    socket::JobSocket,
+    util::serialize,
+    State,
+};
+
+pub async fn status(
+    session: Session,
+    state: web::Data<State>,
+    id: web::Path<i64>,
+) -> ApiJsonResult<Job> {
+    let account = Account::from_session(&session).ok_or_else(|| anyhow!("session error"))?;
+    let client = state.db_pool.get().await?;
+    let (job, _) = job::get(&client, *id)
+        .await?
+        .filter(|(job, _)| job.user_id == account.id())
+        .ok_or(ApiError::JobNotFound(*id))?;
+    serialize(job)
+}
+
+pub async fn register_socket(
+    session: Session,
+    req: web::HttpRequest,
+    stream: web::Payload,
+) -> Result<HttpResponse, actix_web::Error> {
+    let account = Account::from_session(&session).ok_or(ApiError::Unauthorized)?;
+    let resp = actix_web_actors::ws::start(JobSocket::new(account.id()), &req, stream)?;
+    Ok(resp)
+}
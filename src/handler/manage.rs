@@ -6,18 +6,27 @@ use crate::{
         error::AuthError,
         register::{self, RegisterForm},
     },
-    db::rows_into_vec,
+    cache::CacheStats,
+    db::{self, rows_into_vec},
     inc_sql,
-    model::{Permission, Status},
+    model::{
+        page::{Page, PageQuery},
+        queue::{JobFilter, QueuedJob},
+        webhook::Event,
+        Permission, Status,
+    },
+    notify,
+    refresh::{self, ArtworkUpdate},
     socket::LiveFeedSocket,
     util::redirect,
-    State,
+    webhook, State,
 };
 use actix_web::{
     web::{self, Data},
     HttpResponse,
 };
 use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 use tokio_pg_mapper_derive::PostgresMapper;
@@ -26,9 +35,10 @@ use super::{error::ApiError, ApiJsonResult};
 
 pub async fn all_unassigned(state: Data<State>) -> ApiJsonResult<Vec<ModeratorFeed>> {
     let client = state.db_pool.get().await?;
-    let queued_feed_rows = client
-        .query(inc_sql!("get/feed/moderator/all_waiting_for_review"), &[])
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/feed/moderator/all_waiting_for_review"))
         .await?;
+    let queued_feed_rows = client.query(&stmnt, &[]).await?;
     // let reviewed_feed_rows = client
     //     .query(inc_sql!("get/feed/last_reviewed"), &[])
     //     .await?;
@@ -44,13 +54,14 @@ pub async fn all_unassigned(state: Data<State>) -> ApiJsonResult<Vec<ModeratorFe
 
 pub async fn reviewed(state: Data<State>) -> ApiJsonResult<Vec<ModeratorFeed>> {
     let client = state.db_pool.get().await?;
-    let queued_feed_rows = client
-        .query(inc_sql!("get/feed/moderator/reviewed"), &[])
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/feed/moderator/reviewed"))
         .await?;
+    let queued_feed_rows = client.query(&stmnt, &[]).await?;
     serialize(rows_into_vec(queued_feed_rows))
 }
 
-#[derive(Debug, PostgresMapper, Serialize, Clone)]
+#[derive(Debug, PostgresMapper, Serialize, Deserialize, Clone, schemars::JsonSchema)]
 #[pg_mapper(table = "feed")]
 #[serde(rename_all = "camelCase")]
 pub struct ModeratorFeed {
@@ -68,7 +79,7 @@ pub struct ModeratorFeed {
     pub reviewer_name: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReviewPayload {
     action: Status,
@@ -82,17 +93,34 @@ pub async fn review_feed(
     let ReviewPayload { action, feed_ids } = json.into_inner();
     let mut client = state.db_pool.get().await?;
     let trx = client.transaction().await?;
-    let stmnt_review_feed = trx.prepare(inc_sql!("update/review_feed")).await?;
-    let stmnt_update_review = trx.prepare(inc_sql!("update/done_review")).await?;
+    let stmnt_review_feed = trx.prepare_cached(inc_sql!("update/review_feed")).await?;
+    let stmnt_update_review = trx.prepare_cached(inc_sql!("update/done_review")).await?;
+    let mut reviewed = Vec::with_capacity(feed_ids.len());
     for feed_id in feed_ids {
-        trx.execute(&stmnt_review_feed, &[&action, &feed_id])
-            .await?;
+        if trx
+            .execute(&stmnt_review_feed, &[&action, &feed_id])
+            .await?
+            > 0
+        {
+            reviewed.push(feed_id);
+        }
         trx.execute(&stmnt_update_review, &[&feed_id]).await?;
     }
     trx.commit().await?;
+    notify::invalidate_catalogue(&client, &state.cache).await;
+    let event = match action {
+        Status::Online => Some(Event::FeedApproved),
+        Status::Blocked => Some(Event::FeedBlocked),
+        _ => None,
+    };
+    if let Some(event) = event {
+        for feed_id in reviewed {
+            webhook::emit(&client, event, &serde_json::json!({ "feedId": feed_id })).await;
+        }
+    }
     Ok(HttpResponse::Ok().finish())
 }
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AssignPayload {
     feed_ids: Vec<i32>,
@@ -106,7 +134,9 @@ pub async fn reviewer_inbox(
         .map(|a| a.id())
         .ok_or_else(|| anyhow::anyhow!("Session Error"))?;
     let client = state.db_pool.get().await?;
-    let stmnt = client.prepare(inc_sql!("get/feed/moderator/inbox")).await?;
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/feed/moderator/inbox"))
+        .await?;
     let rows = client.query(&stmnt, &[&user_id]).await?;
 
     serialize(rows_into_vec(rows))
@@ -123,7 +153,9 @@ pub async fn assign_for_review(
     let user_id = Account::from_session(&session)
         .map(|a| a.id())
         .ok_or_else(|| anyhow::anyhow!("Session Error"))?;
-    let stmnt = trx.prepare(inc_sql!("/update/assign_for_review")).await?;
+    let stmnt = trx
+        .prepare_cached(inc_sql!("update/assign_for_review"))
+        .await?;
     for feed_id in &json.feed_ids {
         trx.execute(&stmnt, &[&user_id, feed_id]).await?;
     }
@@ -140,6 +172,44 @@ pub async fn register_moderator(
     Ok(redirect("/auth/admin/manage"))
 }
 
+pub async fn refresh_artwork(
+    state: Data<State>,
+    feed_id: web::Path<i32>,
+) -> ApiJsonResult<ArtworkUpdate> {
+    let mut client = state.db_pool.get().await?;
+    let update =
+        refresh::feed_artwork(&mut client, &state.img_cache, feed_id.into_inner(), true).await?;
+    if update.changed {
+        notify::invalidate_catalogue(&client, &state.cache).await;
+    }
+    serialize(update)
+}
+
+const JOB_PAGE_LIMIT: i64 = 50;
+
+/// Jobs of the background queue, most recently changed first.
+pub async fn jobs(
+    state: Data<State>,
+    filter: web::Query<JobFilter>,
+    page: web::Query<PageQuery>,
+) -> ApiJsonResult<Page<QueuedJob>> {
+    let position = page.position(JOB_PAGE_LIMIT)?;
+    let client = state.db_pool.get().await?;
+    serialize(db::queue::all(&client, filter.status, &position).await?)
+}
+
+pub async fn retry_job(state: Data<State>, id: web::Path<i64>) -> ApiJsonResult<QueuedJob> {
+    let client = state.db_pool.get().await?;
+    let job = db::queue::retry(&client, *id)
+        .await?
+        .ok_or(ApiError::FailedJobNotFound(*id))?;
+    serialize(job)
+}
+
+pub async fn cache_stats(state: Data<State>) -> ApiJsonResult<BTreeMap<&'static str, CacheStats>> {
+    serialize(state.cache.stats())
+}
+
 pub async fn register_socket(
     req: web::HttpRequest,
     stream: web::Payload,
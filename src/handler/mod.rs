@@ -2,13 +2,17 @@ pub mod auth;
 pub mod author;
 pub mod category;
 pub mod comment;
+pub mod conditional;
 pub mod episode;
 pub mod error;
 pub mod feed;
+pub mod job;
 pub mod manage;
 pub mod save_preview_feed;
 pub mod subscription;
+pub mod tag;
 pub mod user;
+pub mod webhook;
 use self::error::ApiError;
 use tokio_pg_mapper::FromTokioPostgresRow;
 
@@ -39,34 +43,36 @@ pub async fn serve_img(req: HttpRequest) -> Either<NamedFile, HttpResponse> {
 
 pub async fn image_for_feed(state: web::Data<State>, title: Path<String>) -> ApiJsonResult<Image> {
     let client = state.db_pool.get().await?;
-    let stmnt = client.prepare(inc_sql!("get/feed_image")).await?;
+    let stmnt = client.prepare_cached(inc_sql!("get/feed_image")).await?;
 
     let row = client.query_one(&stmnt, &[&title.decode()]).await?;
     serialize(Image::from_row(row)?)
 }
 
+pub async fn openapi() -> HttpResponse {
+    HttpResponse::Ok().json(crate::openapi::spec())
+}
+
 pub async fn meta(state: web::Data<State>) -> ApiJsonResult<Meta> {
+    if let Some(meta) = state.cache.meta.get("") {
+        return serialize(meta);
+    }
     let client = state.db_pool.get().await?;
-    let count_episodes = client
-        .query_one(inc_sql!("get/meta/count_episode"), &[])
-        .await?
-        .get::<_, i64>(0);
-    let episodes_duration = client
-        .query_one(inc_sql!("get/meta/count_episode_duration"), &[])
-        .await?
-        .get::<_, i64>(0);
-    let count_feeds = client
-        .query_one(inc_sql!("get/meta/count_feed"), &[])
-        .await?
-        .get::<_, i64>(0);
-    let count_authors = client
-        .query_one(inc_sql!("get/meta/count_author"), &[])
-        .await?
-        .get::<_, i64>(0);
-    serialize(Meta {
+    let count_episodes = count(&client, inc_sql!("get/meta/count_episode")).await?;
+    let episodes_duration = count(&client, inc_sql!("get/meta/count_episode_duration")).await?;
+    let count_feeds = count(&client, inc_sql!("get/meta/count_feed")).await?;
+    let count_authors = count(&client, inc_sql!("get/meta/count_author")).await?;
+    let meta = Meta {
         episodes_duration,
         count_episodes,
         count_authors,
         count_feeds,
-    })
+    };
+    state.cache.meta.insert(String::new(), meta.clone());
+    serialize(meta)
+}
+
+async fn count(client: &crate::Client, sql: &str) -> Result<i64, ApiError> {
+    let stmnt = client.prepare_cached(sql).await?;
+    Ok(client.query_one(&stmnt, &[]).await?.get(0))
 }
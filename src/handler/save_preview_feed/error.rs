@@ -1,4 +1,4 @@
-use crate::hide_internal;
+use crate::{handler::error::JsonError, hide_internal};
 use actix_web::http::StatusCode;
 use actix_web::{HttpResponse, ResponseError};
 use std::error::Error as _;
@@ -16,24 +16,39 @@ pub enum PreviewSaveError {
     Fetch(url::Url),
     #[error("Request error: {0}")]
     Request(#[from] reqwest::Error),
+    #[error("The RSS-feed is larger than {0} bytes.")]
+    TooLarge(usize),
     #[error("{0:#?}")]
     Internal(Box<dyn std::error::Error + Send + Sync + 'static>),
     #[error("Can't save the the RSS-feed because this unique field {0} already exists.")]
     Duplicate(Field),
+    #[error("The RSS-feed was already submitted as feed {0}, its {1} match.")]
+    Exists(i32, Field),
+}
+
+impl PreviewSaveError {
+    /// The body `error_response` would send, for errors outside of a request.
+    pub fn to_json_error(&self) -> JsonError {
+        let error = JsonError::new(hide_internal!(PreviewSaveError, self), self.status_code());
+        match self {
+            PreviewSaveError::Exists(feed_id, _) => error.feed_id(*feed_id),
+            _ => error,
+        }
+    }
 }
 
 impl ResponseError for PreviewSaveError {
     fn status_code(&self) -> StatusCode {
         match self {
             PreviewSaveError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            PreviewSaveError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
             _ => StatusCode::BAD_REQUEST,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
         log::error!("{}", self.to_string());
-        // error::JsonError::into_response(hide_internal!(PreviewError, self), self.status_code())
-        crate::json_error!(PreviewSaveError, self)
+        self.to_json_error().into_response()
     }
 }
 
@@ -45,6 +60,10 @@ pub enum Field {
     Url,
     #[error("img")]
     Img,
+    #[error("podcast:guid")]
+    PodcastGuid,
+    #[error("episode GUIDs")]
+    EpisodeGuids,
 }
 
 impl From<tokio_postgres::Error> for PreviewSaveError {
@@ -55,7 +74,9 @@ impl From<tokio_postgres::Error> for PreviewSaveError {
             return match error.code() {
                 Some(code) if code == &SqlState::UNIQUE_VIOLATION => match db_error.constraint() {
                     Some(field) if field == "title" => PreviewSaveError::Duplicate(Field::Title),
-                    Some(field) if field == "url" => PreviewSaveError::Duplicate(Field::Url),
+                    Some(field) if field == "url" || field == "feed_url_url_key" => {
+                        PreviewSaveError::Duplicate(Field::Url)
+                    }
                     Some(field) if field == "img_path" => PreviewSaveError::Duplicate(Field::Img),
                     _ => PreviewSaveError::Internal(error.into()),
                 },
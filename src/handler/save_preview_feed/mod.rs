@@ -6,19 +6,76 @@ use actix_web::web;
 use url::Url;
 
 use self::error::PreviewSaveError;
+use crate::cache::ResponseCache;
 
-async fn fetch(url: &Url) -> Result<web::Bytes, PreviewSaveError> {
-    let bytes = reqwest::get(url.clone())
+/// Larger feeds are rejected before they are parsed.
+pub const MAX_FEED_BYTES: usize = 16 * 1024 * 1024;
+
+pub async fn fetch(url: &Url) -> Result<web::Bytes, PreviewSaveError> {
+    let mut response = reqwest::get(url.clone())
         .await
         .map_err(|_err| PreviewSaveError::Fetch(url.clone()))?
-        .error_for_status()?
-        .bytes()
-        .await?;
-    Ok(bytes)
+        .error_for_status()?;
+    if matches!(response.content_length(), Some(length) if length as usize > MAX_FEED_BYTES) {
+        return Err(PreviewSaveError::TooLarge(MAX_FEED_BYTES));
+    }
+    // the length header is optional, the body is counted while it arrives
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > MAX_FEED_BYTES {
+            return Err(PreviewSaveError::TooLarge(MAX_FEED_BYTES));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body.into())
+}
+
+/// Key of the previews of a user in `ResponseCache::previews`,
+/// a new preview replaces the last one.
+fn preview_key(user_id: i32) -> String {
+    user_id.to_string()
 }
 
-#[derive(serde::Deserialize)]
+pub fn store_preview(cache: &ResponseCache, user_id: i32, url: &Url, bytes: web::Bytes) {
+    cache
+        .previews
+        .insert(preview_key(user_id), (url.clone(), bytes));
+}
+
+/// The previewed bytes of `url`, they are only handed out once to the submission row.
+pub fn take_preview(cache: &ResponseCache, user_id: i32, url: &Url) -> Option<web::Bytes> {
+    cache
+        .previews
+        .remove(&preview_key(user_id))
+        .filter(|(previewed, _)| previewed == url)
+        .map(|(_, bytes)| bytes)
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FeedForm {
     pub feed_url: Url,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_submission_takes_the_previewed_bytes() {
+        let cache = ResponseCache::new();
+        let url = Url::parse("https://example.com/feed.xml").unwrap();
+        let bytes = web::Bytes::from_static(b"<rss/>");
+        store_preview(&cache, 1, &url, bytes.clone());
+        assert_eq!(take_preview(&cache, 2, &url), None);
+        assert_eq!(take_preview(&cache, 1, &url), Some(bytes.clone()));
+        assert_eq!(take_preview(&cache, 1, &url), None);
+
+        let other = Url::parse("https://example.com/other.xml").unwrap();
+        store_preview(&cache, 1, &url, bytes.clone());
+        store_preview(&cache, 1, &other, bytes.clone());
+        assert_eq!(take_preview(&cache, 1, &url), None);
+        store_preview(&cache, 1, &other, bytes.clone());
+        assert_eq!(take_preview(&cache, 1, &other), Some(bytes));
+    }
+}
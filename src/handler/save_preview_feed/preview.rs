@@ -1,30 +1,50 @@
-use crate::{db::feed_exits, model::preview::feed::FeedPreview, State};
+use crate::{
+    db::duplicate,
+    model::{preview::feed::FeedPreview, user::Account},
+    State,
+};
 
 // use actix_broker::{Broker, SystemBroker};
 
+use actix_session::Session;
 use actix_web::{web, HttpResponse};
 
-use super::{error::PreviewSaveError, FeedForm};
+use super::{error::PreviewSaveError, store_preview, FeedForm};
 
 pub async fn create(
     form: web::Json<FeedForm>,
     state: web::Data<State>,
+    ses: Session,
 ) -> Result<HttpResponse, PreviewSaveError> {
     let resp_bytes = super::fetch(&form.feed_url).await?;
     let feed_bytes = std::io::Cursor::new(&resp_bytes);
     let channel = rss::Channel::read_from(feed_bytes)?;
+    if let Some(account) = Account::from_session(&ses) {
+        store_preview(
+            &state.cache,
+            account.id(),
+            &form.feed_url,
+            resp_bytes.clone(),
+        );
+    }
     // let url = form.feed_url.clone();
     // cache_feed_url(&session, url.clone()).map_err(|_| anyhow::anyhow!("session error"))?;
     let client = state.db_pool.get().await?;
     let raw_feed = FeedPreview::parse(&channel, form.feed_url.clone());
-     Ok(HttpResponse::Ok().json(PreviewJson {
-        exists: feed_exits(&client, raw_feed.title, raw_feed.url()).await?,
+    let existing_feed_id = duplicate::find(&client, &raw_feed)
+        .await?
+        .map(|(feed_id, _)| feed_id);
+    Ok(HttpResponse::Ok().json(PreviewJson {
+        exists: existing_feed_id.is_some(),
+        existing_feed_id,
         feed: raw_feed,
     }))
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct PreviewJson<'a> {
     pub exists: bool,
+    pub existing_feed_id: Option<i32>,
     pub feed: FeedPreview<'a>,
 }
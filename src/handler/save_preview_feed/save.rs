@@ -1,59 +1,187 @@
-use actix_broker::{Broker, SystemBroker};
 use actix_session::Session;
 use actix_web::{web, HttpResponse};
+use std::collections::HashSet;
 
 use crate::{
     handler::manage::ModeratorFeed,
-    model::{preview::feed::FeedPreview, user::Account},
-    socket::Message,
-    State,
+    job::{self, Job, Progress},
+    model::{preview::feed::FeedPreview, user::Account, webhook::Event},
+    notify,
+    queue::{self, Task},
+    webhook, State,
 };
 
-use super::{error::PreviewSaveError, fetch, FeedForm};
+use super::{error::PreviewSaveError, fetch, take_preview, FeedForm};
 use crate::db;
 
+/// Queues the submission and answers with the job right away,
+/// its progress is available at `/user/job/{id}` and `/user/socket/jobs`.
 pub async fn save(
     form: web::Json<FeedForm>,
     state: web::Data<State>,
     ses: Session,
 ) -> Result<HttpResponse, PreviewSaveError> {
-    let user_id = Account::from_session(&ses).unwrap().id();
-    let mut client = &mut state.db_pool.get().await?;
-    let feed_url = form.feed_url.clone();
-    let resp_bytes = fetch(&feed_url).await?;
+    let account = Account::from_session(&ses).unwrap();
+    let feed_url = form.into_inner().feed_url;
+    let preview = take_preview(&state.cache, account.id(), &feed_url);
+    let client = state.db_pool.get().await?;
+    let job = job::create(&client, account.id(), feed_url, preview.as_deref()).await?;
+    let task = Task::SubmitFeed {
+        submission_id: job.id,
+    };
+    queue::enqueue(&client, &task).await?;
+    Ok(HttpResponse::Accepted().json(job))
+}
+
+/// Runs a queued submission, see [`Task::SubmitFeed`]. A rejected feed finishes
+/// the job, only internal errors are returned so the queue retries them.
+pub async fn run(
+    state: &State,
+    submission_id: i64,
+    last_attempt: bool,
+) -> Result<(), anyhow::Error> {
+    let client = state.db_pool.get().await?;
+    let (mut job, username) = match job::get(&client, submission_id).await? {
+        Some(found) => found,
+        // forgotten in the meantime
+        None => return Ok(()),
+    };
+    drop(client);
+    if job.progress.is_finished() {
+        return Ok(());
+    }
+    let progress = match job.feed_id {
+        // saved by an attempt that failed afterwards, the feed would count as its own duplicate
+        Some(feed_id) => Progress::Done { feed_id },
+        None => match submit(state, &mut job, &username).await {
+            Ok(feed_id) => Progress::Done { feed_id },
+            Err(err @ PreviewSaveError::Internal(_)) if !last_attempt => {
+                return Err(anyhow::anyhow!("{}", err))
+            }
+            Err(err) => {
+                log::error!("submission job {} failed: {}", job.id, err);
+                Progress::Failed {
+                    error: err.to_json_error(),
+                }
+            }
+        },
+    };
+    let client = state.db_pool.get().await?;
+    job::update(&client, &mut job, progress).await
+}
+
+async fn submit(state: &State, job: &mut Job, username: &str) -> Result<i32, PreviewSaveError> {
+    let mut client = state.db_pool.get().await?;
+    let feed_url = job.feed_url.clone();
+    // kept until the feed is saved, only submissions without a preview fetch the feed
+    let resp_bytes = match db::submission::preview(&client, job.id).await? {
+        Some(bytes) => web::Bytes::from(bytes),
+        None => fetch(&feed_url).await?,
+    };
+    job::update(&client, job, Progress::Fetched).await?;
     let feed_bytes = std::io::Cursor::new(&resp_bytes);
     let channel = rss::Channel::read_from(feed_bytes)?;
     let raw_feed = FeedPreview::parse(&channel, feed_url);
+    let total = raw_feed.episodes.len();
+    job::update(&client, job, Progress::Parsed { episodes: total }).await?;
+    if let Some((feed_id, field)) = db::duplicate::find(&client, &raw_feed).await? {
+        return Err(PreviewSaveError::Exists(feed_id, field));
+    }
     let img_cache = state.img_cache.clone();
     let cached_img = if let Some(img_url) = &raw_feed.img {
-        img_cache.download(img_url).await.ok()
+        img_cache.clone().download(img_url).await.ok()
     } else {
         None
     };
-    if db::feed_exits(&client, raw_feed.title, raw_feed.url()).await? {
-        return Err(PreviewSaveError::Duplicate(super::error::Field::Url));
+    let cover_missing = raw_feed.img.is_some() && cached_img.is_none();
+    // episodes sharing the feed cover reuse its img row
+    let episode_img_urls = raw_feed
+        .episodes
+        .iter()
+        .filter_map(|episode| episode.img.as_ref())
+        .filter(|url| Some(*url) != raw_feed.img.as_ref())
+        .collect::<HashSet<_>>();
+    let episode_imgs = img_cache
+        .download_all(episode_img_urls.into_iter().collect())
+        .await;
+    // the callback cannot wait for the database, a reporter writes the counts in order
+    let (stored_tx, mut stored_rx) = tokio::sync::mpsc::unbounded_channel();
+    let reporter = {
+        let pool = state.db_pool.clone();
+        let mut job = job.clone();
+        actix_web::rt::spawn(async move {
+            while let Some(mut stored) = stored_rx.recv().await {
+                // only the latest count matters when the writes fall behind
+                while let Ok(next) = stored_rx.try_recv() {
+                    stored = next;
+                }
+                let progress = Progress::Storing { stored, total };
+                let result = match pool.get().await {
+                    Ok(client) => job::update(&client, &mut job, progress).await,
+                    Err(err) => Err(err.into()),
+                };
+                if let Err(err) = result {
+                    log::warn!("could not store progress of job {}: {:#}", job.id, err);
+                }
+            }
+        })
+    };
+    let saved = {
+        let on_stored = |stored: usize| {
+            let _ = stored_tx.send(stored);
+        };
+        on_stored(0);
+        crate::db::feed::save(
+            &mut client,
+            &raw_feed,
+            job.user_id,
+            cached_img,
+            &episode_imgs,
+            &on_stored,
+            job.id,
+        )
+        .await
+    };
+    drop(stored_tx);
+    if let Err(err) = reporter.await {
+        log::warn!("progress reporter of job {} stopped: {}", job.id, err);
+    }
+    let feed_id = saved?;
+    notify::invalidate_catalogue(&client, &state.cache).await;
+    if cover_missing {
+        // the cover is fetched again in the background until the host answers
+        let task = Task::RefreshArtwork {
+            feed_id,
+            force: true,
+        };
+        if let Err(err) = queue::enqueue(&client, &task).await {
+            log::error!("could not queue artwork of feed {}: {}", feed_id, err);
+        }
+    }
+    let submitted = serde_json::json!({
+        "feedId": feed_id,
+        "url": raw_feed.url().to_string(),
+        "title": raw_feed.title,
+    });
+    webhook::emit(&client, Event::FeedSubmitted, &submitted).await;
+    if total > 0 {
+        let ingested = serde_json::json!({ "feedId": feed_id, "episodes": total });
+        webhook::emit(&client, Event::EpisodesIngested, &ingested).await;
     }
-    let feed_id = crate::db::feed::save(&mut client, &raw_feed, user_id, cached_img).await?;
     let now = chrono::offset::Utc::now();
 
-    let feed_message = Message::new(ModeratorFeed {
+    let moderator_feed = ModeratorFeed {
         id: feed_id,
         url: raw_feed.url().to_string(),
         title: raw_feed.title.to_string(),
         author_name: raw_feed.author_name.unwrap_or("default name").to_string(),
         link_web: raw_feed.link_web.map(|u| u.to_string()),
         submitted: now,
-        username: Account::from_session(&ses).unwrap().username().to_owned(),
+        username: username.to_owned(),
         status: crate::model::Status::Queued,
         modified: now,
         reviewer_name: None,
-    });
-
-    Broker::<SystemBroker>::issue_async(feed_message);
-    Ok(HttpResponse::Ok().json(SavedJson { feed_id }))
-}
-
-#[derive(Debug, serde::Serialize)]
-pub struct SavedJson {
-    feed_id: i32,
+    };
+    notify::publish(&client, moderator_feed).await;
+    Ok(feed_id)
 }
@@ -12,7 +12,7 @@ use crate::{
 
 use super::{error::ApiError, ApiJsonResult};
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SubscribePayload {
     feed_id: i32,
@@ -0,0 +1,75 @@
+use crate::{
+    db::rows_into_vec,
+    inc_sql,
+    model::{
+        feed::Feed,
+        page::{Page, PageQuery},
+        preview::episode::normalize_keyword,
+        search::EpisodeHit,
+        Tag,
+    },
+    path::Path,
+    util::serialize,
+    State,
+};
+use actix_web::web;
+
+use super::{error::ApiError, ApiJsonResult};
+
+const TAG_LIMIT_DEFAULT: i64 = 100;
+const TAG_LIMIT_MAX: i64 = 500;
+const TAG_PAGE_LIMIT: i64 = 50;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct TagQuery {
+    limit: Option<i64>,
+}
+
+/// Most used episode keywords, suited for a tag cloud.
+pub async fn all(state: web::Data<State>, query: web::Query<TagQuery>) -> ApiJsonResult<Vec<Tag>> {
+    let limit = query
+        .limit
+        .unwrap_or(TAG_LIMIT_DEFAULT)
+        .clamp(1, TAG_LIMIT_MAX);
+    let client = state.db_pool.get().await?;
+    let stmnt = client.prepare_cached(inc_sql!("get/tag/all")).await?;
+    let rows = client.query(&stmnt, &[&limit]).await?;
+    serialize(rows_into_vec(rows))
+}
+
+pub async fn episodes(
+    state: web::Data<State>,
+    name: Path<String>,
+    page: web::Query<PageQuery>,
+) -> ApiJsonResult<Page<EpisodeHit>> {
+    let tag = normalize_keyword(&name.decode());
+    let position = page.position(TAG_PAGE_LIMIT)?;
+    let client = state.db_pool.get().await?;
+    let stmnt = client.prepare_cached(inc_sql!("get/tag/episodes")).await?;
+    let rows = client.query(&stmnt, &position.params(&[&tag])).await?;
+    if rows.is_empty() && position.is_first() {
+        return Err(ApiError::TagNotFound(tag));
+    }
+    serialize(position.page(rows).map(EpisodeHit::from))
+}
+
+pub async fn feeds(
+    state: web::Data<State>,
+    name: Path<String>,
+    page: web::Query<PageQuery>,
+) -> ApiJsonResult<Page<Feed>> {
+    let tag = normalize_keyword(&name.decode());
+    let position = page.position(TAG_PAGE_LIMIT)?;
+    let client = state.db_pool.get().await?;
+    let stmnt = client.prepare_cached(inc_sql!("get/tag/feeds")).await?;
+    let rows = client.query(&stmnt, &position.params(&[&tag])).await?;
+    if rows.is_empty() && position.is_first() {
+        return Err(ApiError::TagNotFound(tag));
+    }
+    let Page { items, next_cursor } = position.page(rows);
+    let feeds = Feed::from_rows(&client, items).await?;
+    serialize(Page {
+        items: feeds,
+        next_cursor,
+    })
+}
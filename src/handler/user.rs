@@ -1,11 +1,12 @@
 use super::error::ApiError;
-use crate::db::rows_into_vec;
 use crate::model::feed::TinyFeed;
-use crate::model::user::SubmittedFeeds;
+use crate::model::page::{Page, PageQuery};
+use crate::model::user::SubmittedFilter;
 use crate::State;
 use crate::{
     inc_sql,
     model::{user::Account, Status},
+    notify,
 };
 use actix_session::Session;
 use actix_web::{
@@ -15,50 +16,51 @@ use actix_web::{
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 
-fn filter_feeds(feeds: &[TinyFeed], status: Status) -> Vec<TinyFeed> {
-    feeds
-        .into_iter()
-        .filter(|feed| feed.status == status)
-        .cloned()
-        .map(|feed| feed.to_owned().clone())
-        .collect::<Vec<TinyFeed>>()
-}
+const SUBMITTED_PAGE_LIMIT: i64 = 50;
 
+/// Feeds the user submitted, most recent first, optionally of one status.
 pub async fn submitted_feeds(
     session: Session,
     state: web::Data<State>,
+    filter: web::Query<SubmittedFilter>,
+    page: web::Query<PageQuery>,
 ) -> Result<HttpResponse, ApiError> {
     let account = Account::from_session(&session).ok_or_else(|| anyhow!("session error"))?;
+    let position = page.position(SUBMITTED_PAGE_LIMIT)?;
     let client = state.db_pool.get().await?;
-    let stmnt = client.prepare(inc_sql!("get/feed/user/submitted")).await?;
-    let rows = client.query(&stmnt, &[&account.id()]).await?;
-    let feeds: Vec<TinyFeed> = rows_into_vec(rows);
-    let feeds_json = SubmittedFeeds {
-        blocked: filter_feeds(&feeds, Status::Blocked),
-        online: filter_feeds(&feeds, Status::Online),
-        offline: filter_feeds(&feeds, Status::Offline),
-        queued: filter_feeds(&feeds, Status::Queued),
-    };
+    let stmnt = client
+        .prepare_cached(inc_sql!("get/feed/user/submitted"))
+        .await?;
+    let rows = client
+        .query(&stmnt, &position.params(&[&account.id(), &filter.status]))
+        .await?;
+    let feeds: Page<TinyFeed> = position.page(rows).rows_into();
 
-    Ok(HttpResponse::Ok().json(feeds_json))
+    Ok(HttpResponse::Ok().json(feeds))
 }
 
+const SUBSCRIPTION_PAGE_LIMIT: i64 = 50;
+
 pub async fn subscriptions(
     session: Session,
     state: web::Data<State>,
+    page: web::Query<PageQuery>,
 ) -> Result<HttpResponse, ApiError> {
     let account = Account::from_session(&session).ok_or_else(|| anyhow!("session error"))?;
+    let position = page.position(SUBSCRIPTION_PAGE_LIMIT)?;
     let client = state.db_pool.get().await?;
     let stmnt = client
-        .prepare(inc_sql!("get/feed/user/subscription"))
+        .prepare_cached(inc_sql!("get/feed/user/subscription"))
         .await?;
 
-    let rows = client.query(&stmnt, &[&account.id()]).await?;
-    let feeds: Vec<TinyFeed> = rows_into_vec(rows);
+    let rows = client
+        .query(&stmnt, &position.params(&[&account.id()]))
+        .await?;
+    let feeds = position.page(rows).rows_into::<TinyFeed>();
     Ok(HttpResponse::Ok().json(feeds))
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdatePayload {
     action: Status,
@@ -76,9 +78,12 @@ pub async fn update_feed(
     let UpdatePayload { action, feed_id } = json.into_inner();
     let mut client = state.db_pool.get().await?;
     let trx = client.transaction().await?;
-    let stmnt = trx.prepare(inc_sql!("update/user_feed_visibility")).await?;
+    let stmnt = trx
+        .prepare_cached(inc_sql!("update/user_feed_visibility"))
+        .await?;
     trx.execute(&stmnt, &[&action, &feed_id, &account_id])
         .await?;
     trx.commit().await?;
+    notify::invalidate_catalogue(&client, &state.cache).await;
     Ok(HttpResponse::Ok().finish())
 }
@@ -0,0 +1,70 @@
+use actix_web::{web, HttpResponse};
+
+use super::{error::ApiError, ApiJsonResult};
+use crate::{
+    db,
+    model::{
+        page::{Page, PageQuery},
+        webhook::{CreatedWebhook, Delivery, NewWebhook, Webhook},
+    },
+    util::serialize,
+    webhook::generate_secret,
+    State,
+};
+
+const DELIVERY_PAGE_LIMIT: i64 = 50;
+
+pub async fn all(state: web::Data<State>) -> ApiJsonResult<Vec<Webhook>> {
+    let client = state.db_pool.get().await?;
+    serialize(db::webhook::all(&client).await?)
+}
+
+/// Registers a webhook, the response is the only place its secret is shown.
+pub async fn create(
+    state: web::Data<State>,
+    json: web::Json<NewWebhook>,
+) -> Result<HttpResponse, ApiError> {
+    let NewWebhook {
+        url,
+        mut events,
+        secret,
+    } = json.into_inner();
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(ApiError::InvalidWebhook("url must use http or https"));
+    }
+    events.sort_by_key(|event| event.as_str());
+    events.dedup();
+    if events.is_empty() {
+        return Err(ApiError::InvalidWebhook("no events"));
+    }
+    let secret = secret
+        .filter(|secret| !secret.is_empty())
+        .unwrap_or_else(generate_secret);
+    let events = events
+        .iter()
+        .map(|event| event.as_str())
+        .collect::<Vec<_>>();
+
+    let client = state.db_pool.get().await?;
+    let webhook = db::webhook::insert(&client, url.as_str(), &secret, &events).await?;
+    Ok(HttpResponse::Created().json(CreatedWebhook { webhook, secret }))
+}
+
+pub async fn delete(state: web::Data<State>, id: web::Path<i32>) -> Result<HttpResponse, ApiError> {
+    let client = state.db_pool.get().await?;
+    if !db::webhook::delete(&client, *id).await? {
+        return Err(ApiError::WebhookNotFound(*id));
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Delivery log of a webhook, newest first.
+pub async fn deliveries(
+    state: web::Data<State>,
+    id: web::Path<i32>,
+    page: web::Query<PageQuery>,
+) -> ApiJsonResult<Page<Delivery>> {
+    let position = page.position(DELIVERY_PAGE_LIMIT)?;
+    let client = state.db_pool.get().await?;
+    serialize(db::webhook::deliveries(&client, *id, &position).await?)
+}
@@ -1,5 +1,6 @@
+use futures_util::{future, stream, StreamExt};
 use reqwest::Url;
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use tree_magic_mini::{self, match_u8};
 #[derive(Debug, Clone)]
@@ -12,6 +13,8 @@ pub struct RowImg<'a> {
     pub hash: String,
     pub file_name: String,
     pub link: &'a Url,
+    pub blurhash: Option<String>,
+    pub color: Option<String>,
 }
 
 impl ImageCache {
@@ -31,7 +34,7 @@ impl ImageCache {
             Ok(_) => Ok(()),
             Err(err) => {
                 if err.kind() == std::io::ErrorKind::NotFound {
-                    tokio::fs::write(&self.path, bytes).await?;
+                    tokio::fs::write(&self.path, &bytes).await?;
                     Ok(())
                 } else {
                     Err(err)
@@ -39,13 +42,53 @@ impl ImageCache {
             }
         }
         .map_err(|e| e)?;
+        let placeholder = tokio::task::spawn_blocking(move || Placeholder::compute(&bytes))
+            .await
+            .ok()
+            .flatten();
+        let (blurhash, color) = match placeholder {
+            Some(Placeholder { blurhash, color }) => (Some(blurhash), Some(color)),
+            None => (None, None),
+        };
         Ok(RowImg {
             link: url,
             file_name: format!("{}.{}", &hash, extension),
             hash,
+            blurhash,
+            color,
         })
     }
 
+    /// Downloads several images with a few requests in flight at once.
+    /// Images that fail to download are logged and left out.
+    pub async fn download_all<'a>(&self, urls: Vec<&'a Url>) -> Vec<RowImg<'a>> {
+        stream::iter(urls)
+            .map(|url| self.clone().download(url))
+            .buffer_unordered(4)
+            .filter_map(|ret| {
+                future::ready(
+                    ret.map_err(|err| log::warn!("could not cache img: {:?}", err))
+                        .ok(),
+                )
+            })
+            .collect()
+            .await
+    }
+
+    /// Blurhash and colour of an image that is already cached, `None` if it can not be decoded.
+    pub async fn placeholder(
+        &self,
+        file_name: &str,
+    ) -> Result<Option<(String, String)>, anyhow::Error> {
+        let bytes = tokio::fs::read(self.path.join(file_name)).await?;
+        let placeholder = tokio::task::spawn_blocking(move || Placeholder::compute(&bytes)).await?;
+        Ok(placeholder.map(|Placeholder { blurhash, color }| (blurhash, color)))
+    }
+
+    pub async fn remove(&self, file_name: &str) -> Result<(), std::io::Error> {
+        tokio::fs::remove_file(self.path.join(file_name)).await
+    }
+
     fn hash_and_set_path(&mut self, bytes: &[u8], extension: &str) -> String {
         let hash = blake3::hash(&bytes).to_hex().to_string();
         self.path = self.path.join(&hash);
@@ -54,6 +97,51 @@ impl ImageCache {
     }
 }
 
+/// Blurred preview and background colour shown while the real image loads.
+struct Placeholder {
+    blurhash: String,
+    color: String,
+}
+
+impl Placeholder {
+    fn compute(bytes: &[u8]) -> Option<Self> {
+        let thumbnail = image::load_from_memory(bytes)
+            .map_err(|err| log::warn!("could not decode img: {:?}", err))
+            .ok()?
+            .thumbnail(32, 32)
+            .to_rgba8();
+        let (width, height) = thumbnail.dimensions();
+        let blurhash = blurhash::encode(4, 3, width, height, thumbnail.as_raw()).ok()?;
+        Some(Self {
+            blurhash,
+            color: dominant_color(thumbnail.as_raw())?,
+        })
+    }
+}
+
+// most common colour after reducing every channel to 16 levels,
+// averaged over the pixels of that bucket
+fn dominant_color(rgba: &[u8]) -> Option<String> {
+    let mut buckets: HashMap<u16, (u32, [u32; 3])> = HashMap::new();
+    for pixel in rgba.chunks_exact(4).filter(|pixel| pixel[3] >= 128) {
+        let key = (u16::from(pixel[0] >> 4) << 8)
+            | (u16::from(pixel[1] >> 4) << 4)
+            | u16::from(pixel[2] >> 4);
+        let (count, sum) = buckets.entry(key).or_insert((0, [0; 3]));
+        *count += 1;
+        for (channel, value) in sum.iter_mut().zip(pixel) {
+            *channel += u32::from(*value);
+        }
+    }
+    let (count, sum) = buckets.values().max_by_key(|(count, _)| *count)?;
+    Some(format!(
+        "#{:02x}{:02x}{:02x}",
+        sum[0] / count,
+        sum[1] / count,
+        sum[2] / count
+    ))
+}
+
 fn extension_from_guessed_mime(bytes: &[u8]) -> Result<&'static str, anyhow::Error> {
     if match_u8(mime::IMAGE_JPEG.as_ref(), bytes) {
         Ok("jpeg")
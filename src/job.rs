@@ -0,0 +1,152 @@
+//! Feed submissions run as `submit_feed` tasks on the job queue. Their progress is
+//! stored in the `submission` table, so every instance can answer `/user/job/{id}`,
+//! and announced with `NOTIFY` to the `/user/socket/jobs` of all instances.
+use chrono::{DateTime, Utc};
+use url::Url;
+
+use crate::{db, handler::error::JsonError, notify, Client};
+
+/// Finished jobs stay queryable for this long.
+const KEEP_FINISHED_MINUTES: i32 = 60;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "state")]
+pub enum Progress {
+    Queued,
+    Fetched,
+    #[serde(rename_all = "camelCase")]
+    Parsed {
+        episodes: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    Storing {
+        stored: usize,
+        total: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    Done {
+        feed_id: i32,
+    },
+    Failed {
+        error: JsonError,
+    },
+}
+
+impl Progress {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Progress::Done { .. } | Progress::Failed { .. })
+    }
+}
+
+#[derive(
+    Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema, actix::Message,
+)]
+#[rtype(result = "()")]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: i64,
+    #[serde(skip)]
+    pub user_id: i32,
+    pub feed_url: Url,
+    pub progress: Progress,
+    pub updated: DateTime<Utc>,
+    /// Set once the feed is saved, even when the job could not be finished.
+    #[serde(skip)]
+    pub feed_id: Option<i32>,
+}
+
+impl Job {
+    fn from_row(submission: db::submission::Submission) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            id: submission.id,
+            user_id: submission.account_id,
+            feed_url: Url::parse(&submission.feed_url)?,
+            progress: serde_json::from_value(submission.progress)?,
+            updated: submission.updated,
+            feed_id: submission.feed_id,
+        })
+    }
+}
+
+/// Registers a queued job and forgets jobs that finished a while ago. The previewed
+/// bytes are kept with the job, so any instance saves what the user has seen.
+pub async fn create(
+    client: &Client,
+    user_id: i32,
+    feed_url: Url,
+    preview: Option<&[u8]>,
+) -> Result<Job, anyhow::Error> {
+    db::submission::delete_finished(client, KEEP_FINISHED_MINUTES).await?;
+    let progress = Progress::Queued;
+    let (id, updated) = db::submission::insert(
+        client,
+        user_id,
+        feed_url.as_str(),
+        &serde_json::to_value(&progress)?,
+        preview,
+    )
+    .await?;
+    Ok(Job {
+        id,
+        user_id,
+        feed_url,
+        progress,
+        updated,
+        feed_id: None,
+    })
+}
+
+/// The job with the username of its submitter.
+pub async fn get(client: &Client, id: i64) -> Result<Option<(Job, String)>, anyhow::Error> {
+    match db::submission::get(client, id).await? {
+        Some(submission) => {
+            let username = submission.username.clone();
+            Ok(Some((Job::from_row(submission)?, username)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Stores the new progress and announces it to the sockets of the submitter.
+pub async fn update(
+    client: &Client,
+    job: &mut Job,
+    progress: Progress,
+) -> Result<(), anyhow::Error> {
+    job.updated =
+        db::submission::update_progress(client, job.id, &serde_json::to_value(&progress)?).await?;
+    job.progress = progress;
+    notify::publish_job(client, job.clone()).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::http::StatusCode;
+
+    #[test]
+    fn test_progress_row_round_trip() {
+        let error = JsonError::new("feed exists".to_string(), StatusCode::CONFLICT).feed_id(4);
+        for progress in vec![
+            Progress::Queued,
+            Progress::Storing {
+                stored: 10,
+                total: 20,
+            },
+            Progress::Failed { error },
+        ] {
+            let value = serde_json::to_value(&progress).unwrap();
+            let decoded: Progress = serde_json::from_value(value.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&decoded).unwrap(), value);
+        }
+        // `delete/finished_submissions` relies on the state tag
+        let done = serde_json::to_value(&Progress::Done { feed_id: 1 }).unwrap();
+        assert_eq!(done["state"], "done");
+        let failed = serde_json::to_value(&Progress::Failed {
+            error: JsonError::new(String::new(), StatusCode::BAD_REQUEST),
+        })
+        .unwrap();
+        assert_eq!(failed["state"], "failed");
+    }
+}
@@ -11,18 +11,25 @@ use actix_web::{
 use img_cache::ImageCache;
 
 mod auth;
+mod cache;
 mod db;
 mod handler;
 mod routes;
 mod util;
 use deadpool_postgres::Pool;
 mod img_cache;
+mod job;
 mod macros;
 mod model;
 mod my_middleware;
+mod notify;
+mod openapi;
 mod path;
+mod queue;
+mod refresh;
 mod socket;
 mod time_date;
+mod webhook;
 
 pub type Client = deadpool_postgres::Client;
 
@@ -30,19 +37,31 @@ pub type Client = deadpool_postgres::Client;
 pub struct State {
     db_pool: Pool,
     img_cache: ImageCache,
+    cache: cache::ResponseCache,
 }
 
 async fn run() -> Result<(), anyhow::Error> {
     let state = State {
         db_pool: db::util::connect_and_migrate().await?,
         img_cache: ImageCache::new("img-cache").await?,
+        cache: cache::ResponseCache::new(),
     };
 
     if std::env::var_os("RUST_LOG").is_none() {
         std::env::set_var("RUST_LOG", "info");
     }
 
+    let workers = std::env::var("QUEUE_WORKERS")
+        .ok()
+        .and_then(|workers| workers.parse().ok())
+        .unwrap_or(2);
+    queue::start(state.clone(), workers);
+    refresh::start(state.clone());
+    notify::start(state.cache.clone());
+
     let cookie_config = CookieConfig::new();
+    let sunset = std::env::var("API_SUNSET").ok();
+    let deprecated = my_middleware::deprecation::Deprecated::new(sunset.as_deref())?;
     HttpServer::new(move || {
         App::new()
             .app_data(Data::new(state.clone()))
@@ -80,10 +99,13 @@ async fn run() -> Result<(), anyhow::Error> {
                 "/img/{file_name:.+(jpeg|jpg|png)$}",
                 web::get().to(handler::serve_img),
             )
-            .configure(routes::api)
-            .configure(routes::auth)
-            .configure(routes::user)
-            .configure(routes::moderator)
+            .service(web::scope(routes::API_V1).configure(routes::configure))
+            // the unversioned paths stay as aliases until the sunset date
+            .service(
+                web::scope("")
+                    .wrap(deprecated.clone())
+                    .configure(routes::configure),
+            )
             .default_service(web::route().to(handler::error::not_found))
     })
     .bind("0.0.0.0:8020")?
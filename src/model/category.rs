@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tokio_pg_mapper_derive::PostgresMapper;
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Category {
     id: i32,
@@ -9,7 +9,7 @@ pub struct Category {
     pub children: Vec<SubCategory>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PostgresMapper)]
+#[derive(Clone, Debug, Serialize, Deserialize, PostgresMapper, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 #[pg_mapper(table = "subCategory")]
 pub struct SubCategory {
@@ -1,20 +1,23 @@
-use crate::db::category::get_categories_for_feed;
+use crate::db::{
+    category::{categories_for_feeds, get_categories_for_feed},
+    QueryRows,
+};
 use crate::time_date::serialize_datetime;
-use crate::{db, Client};
+use crate::Client;
 use crate::{handler::error::ApiError, util::LanguageCodeLookup};
 use chrono::{DateTime, Utc};
-use db::episode;
-use episode::episode_offset;
 use reqwest::Url;
 use serde::Serialize;
 
 use super::category::Category;
-use super::preview::episode::{Episode, EpisodeNext};
+use super::page::Page;
+use super::preview::episode::Episode;
+use super::search::Highlight;
 use super::Status;
 
 use tokio_pg_mapper_derive::PostgresMapper;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Feed {
     pub id: i32,
@@ -27,11 +30,15 @@ pub struct Feed {
     pub subtitle: Option<String>,
     pub language: Option<String>,
     pub img_cache: Option<String>,
+    pub img_blurhash: Option<String>,
+    pub img_color: Option<String>,
     #[serde(serialize_with = "serialize_datetime")]
     pub submitted: DateTime<Utc>,
     pub categories: Vec<Category>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub episodes: Option<EpisodeNext>,
+    pub episodes: Option<Page<Episode>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<Highlight>,
 }
 
 impl LanguageCodeLookup for Feed {
@@ -44,18 +51,34 @@ impl Feed {
     pub async fn from(
         client: &Client,
         row: tokio_postgres::Row,
-        episodes: Option<Vec<Episode>>,
+        episodes: Option<Page<Episode>>,
     ) -> Result<Self, ApiError> {
-        let id = row.get("id");
+        let categories = get_categories_for_feed(client, row.get("id")).await?;
+        Self::with_categories(row, categories, episodes)
+    }
 
-        let epsiodes_next = if let Some(items) = episodes {
-            Some(EpisodeNext {
-                offset: episode_offset(&client, &items, &id).await?,
-                items,
+    /// Builds a list of feeds, the categories of all of them are loaded at once.
+    pub async fn from_rows<C: QueryRows>(
+        client: &C,
+        rows: Vec<tokio_postgres::Row>,
+    ) -> Result<Vec<Self>, ApiError> {
+        let feed_ids = rows.iter().map(|row| row.get("id")).collect::<Vec<i32>>();
+        let mut categories = categories_for_feeds(client, &feed_ids).await?;
+        rows.into_iter()
+            .map(|row| {
+                let feed_id: i32 = row.get("id");
+                let categories = categories.remove(&feed_id).unwrap_or_default();
+                Self::with_categories(row, categories, None)
             })
-        } else {
-            None
-        };
+            .collect()
+    }
+
+    fn with_categories(
+        row: tokio_postgres::Row,
+        categories: Vec<Category>,
+        episodes: Option<Page<Episode>>,
+    ) -> Result<Self, ApiError> {
+        let id = row.get("id");
 
         Ok(Self {
             id,
@@ -69,8 +92,11 @@ impl Feed {
             language: row.get("language"),
             submitted: row.get("submitted"),
             img_cache: row.get("img_cache"),
-            categories: get_categories_for_feed(&client, id).await?,
-            episodes: epsiodes_next,
+            img_blurhash: row.get("img_blurhash"),
+            img_color: row.get("img_color"),
+            categories,
+            episodes,
+            highlight: Highlight::from_row(&row),
         })
     }
 }
@@ -83,7 +109,7 @@ fn parse_url(row: &tokio_postgres::Row) -> Option<Url> {
     }
 }
 
-#[derive(Debug, PostgresMapper, Serialize, Clone)]
+#[derive(Debug, PostgresMapper, Serialize, Clone, schemars::JsonSchema)]
 #[pg_mapper(table = "profilefeed")]
 #[serde(rename_all = "camelCase")]
 pub struct TinyFeed {
@@ -91,13 +117,15 @@ pub struct TinyFeed {
     pub title: String,
     pub subtitle: Option<String>,
     pub img: Option<String>,
+    pub img_blurhash: Option<String>,
+    pub img_color: Option<String>,
     pub author_name: String,
     pub status: super::Status,
     #[serde(serialize_with = "serialize_datetime")]
     pub submitted: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FeedUserMeta {
     pub has_subscribed: bool,
@@ -105,10 +133,83 @@ pub struct FeedUserMeta {
     pub status: Option<Status>,
 }
 
-#[derive(Debug, Clone, Serialize, PostgresMapper)]
+#[derive(Debug, Clone, Serialize, PostgresMapper, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 #[pg_mapper(table = "image")]
 pub struct Image {
     pub feed_id: i32,
     pub img: Option<String>,
+    pub blurhash: Option<String>,
+    pub color: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures_util::future::LocalBoxFuture;
+    use std::cell::Cell;
+    use tokio_postgres::{types::ToSql, Row};
+
+    /// Forwards to the database and counts the round trips, a statement missing from
+    /// the cache of `prepare_cached` costs one more for the prepare.
+    struct CountingClient<'c> {
+        client: &'c Client,
+        round_trips: Cell<usize>,
+    }
+
+    impl QueryRows for CountingClient<'_> {
+        fn query_rows<'a>(
+            &'a self,
+            sql: &'a str,
+            params: &'a [&'a (dyn ToSql + Sync)],
+        ) -> LocalBoxFuture<'a, Result<Vec<Row>, tokio_postgres::Error>> {
+            Box::pin(async move {
+                let cached = self.client.statement_cache.size();
+                let rows = self.client.query_rows(sql, params).await;
+                let prepared = self.client.statement_cache.size() - cached;
+                self.round_trips.set(self.round_trips.get() + prepared + 1);
+                rows
+            })
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs the DB_* environment of a migrated database, runs in CI"]
+    async fn test_from_rows_loads_categories_in_one_query() {
+        let pool = crate::db::util::connect_and_migrate().await.unwrap();
+        let client = pool.get().await.unwrap();
+        // the columns of a feed listing, ids past any real feed
+        let rows = || {
+            client.query(
+                "SELECT id, 'https://example.com/' || id as url, 'Feed ' || id as title, \
+                 'Author' as author_name, NULL::text as img, NULL::text as link_web, \
+                 '' as description, NULL::text as subtitle, NULL::text as language, \
+                 now() as submitted, NULL::text as img_cache, NULL::text as img_blurhash, \
+                 NULL::text as img_color \
+                 FROM generate_series(-50, -1) as id",
+                &[],
+            )
+        };
+        let counting = CountingClient {
+            client: &client,
+            round_trips: Cell::new(0),
+        };
+        client.statement_cache.clear();
+
+        // the prepare of the category statement and its query
+        let feeds = Feed::from_rows(&counting, rows().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(feeds.len(), 50);
+        assert_eq!(counting.round_trips.get(), 2);
+
+        // the statement is cached now
+        Feed::from_rows(&counting, rows().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(counting.round_trips.get(), 3);
+
+        Feed::from_rows(&counting, Vec::new()).await.unwrap();
+        assert_eq!(counting.round_trips.get(), 3);
+    }
 }
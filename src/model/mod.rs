@@ -1,8 +1,12 @@
 pub mod category;
 
 pub mod feed;
+pub mod page;
 pub mod preview;
+pub mod queue;
+pub mod search;
 pub mod user;
+pub mod webhook;
 
 use crate::time_date::serialize_datetime;
 use chrono::Utc;
@@ -14,7 +18,9 @@ use tokio_postgres::Row;
 
 use self::user::ShortAccount;
 
-#[derive(Debug, ToSql, FromSql, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[derive(
+    Debug, ToSql, FromSql, Serialize, Deserialize, Clone, Copy, PartialEq, schemars::JsonSchema,
+)]
 #[postgres(name = "permission")]
 pub enum Permission {
     #[postgres(name = "admin")]
@@ -23,7 +29,7 @@ pub enum Permission {
     User,
 }
 
-#[derive(Debug, Deserialize, Serialize, ToSql, FromSql, PartialEq, Clone)]
+#[derive(Debug, Deserialize, Serialize, ToSql, FromSql, PartialEq, Clone, schemars::JsonSchema)]
 #[postgres(name = "feed_status")]
 pub enum Status {
     #[postgres(name = "online")]
@@ -42,15 +48,46 @@ impl fmt::Display for Status {
     }
 }
 
-#[derive(Debug, Serialize, PostgresMapper)]
-#[pg_mapper(table = "completion")]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct Completion {
+pub enum SuggestionKind {
+    Feed,
+    Author,
+    Category,
+    Episode,
+}
+
+/// Typed autocompletion entry, `subtitle` is the author of a feed
+/// or the feed of an episode.
+#[derive(Debug, Serialize, Clone, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    id: i64,
+    kind: SuggestionKind,
     title: String,
-    author_name: String,
+    subtitle: Option<String>,
+    img: Option<String>,
+}
+
+impl From<Row> for Suggestion {
+    fn from(row: Row) -> Self {
+        let kind = match row.get::<_, &str>("kind") {
+            "author" => SuggestionKind::Author,
+            "category" => SuggestionKind::Category,
+            "episode" => SuggestionKind::Episode,
+            _ => SuggestionKind::Feed,
+        };
+        Self {
+            id: row.get("id"),
+            kind,
+            title: row.get("title"),
+            subtitle: row.get("subtitle"),
+            img: row.get("img"),
+        }
+    }
 }
 
-#[derive(Debug, PostgresMapper, Serialize)]
+#[derive(Debug, Clone, PostgresMapper, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 #[pg_mapper(table = "author")]
 pub struct Author {
@@ -58,7 +95,16 @@ pub struct Author {
     pub name: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Episode keyword with the number of online episodes carrying it.
+#[derive(Debug, PostgresMapper, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[pg_mapper(table = "tag")]
+pub struct Tag {
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NewComment {
     pub user_id: i32,
@@ -66,7 +112,7 @@ pub struct NewComment {
     pub content: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Comment {
     id: i32,
@@ -92,7 +138,7 @@ impl From<Row> for Comment {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Meta {
     pub episodes_duration: i64,
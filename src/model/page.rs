@@ -0,0 +1,154 @@
+//! Cursor pagination shared by the list endpoints.
+//!
+//! Every paginated query orders by a sort value plus the row id, selects both as
+//! `cursor_key` (text) and `cursor_id` (int8) and takes the cursor key, the cursor id
+//! and the row limit as its last three parameters, see [`Position::params`].
+use serde::{Deserialize, Serialize};
+use tokio_pg_mapper::FromTokioPostgresRow;
+use tokio_postgres::{types::ToSql, Row};
+
+use crate::{db::rows_into_vec, handler::error::ApiError};
+
+pub const PAGE_LIMIT_MAX: i64 = 100;
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Opaque cursor of the next page, `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U, F: FnMut(T) -> U>(self, f: F) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+impl Page<Row> {
+    pub fn rows_into<T: FromTokioPostgresRow>(self) -> Page<T> {
+        Page {
+            items: rows_into_vec(self.items),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct PageQuery {
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+impl PageQuery {
+    /// Identifies the requested page in a response cache.
+    pub fn cache_key(&self) -> String {
+        format!(
+            "{}:{}",
+            self.limit.unwrap_or_default(),
+            self.cursor.as_deref().unwrap_or_default()
+        )
+    }
+
+    pub fn position(&self, default_limit: i64) -> Result<Position, ApiError> {
+        let cursor = match &self.cursor {
+            Some(cursor) => Some(
+                Cursor::decode(cursor).ok_or_else(|| ApiError::InvalidCursor(cursor.clone()))?,
+            ),
+            None => None,
+        };
+        let limit = self.limit.unwrap_or(default_limit).clamp(1, PAGE_LIMIT_MAX);
+        Ok(Position {
+            key: cursor.as_ref().map(|c| c.key.clone()),
+            id: cursor.map(|c| c.id),
+            // one extra row tells whether another page follows
+            fetch: limit + 1,
+            limit: limit as usize,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Position {
+    key: Option<String>,
+    id: Option<i64>,
+    fetch: i64,
+    limit: usize,
+}
+
+impl Position {
+    pub fn is_first(&self) -> bool {
+        self.key.is_none()
+    }
+
+    /// The query parameters followed by cursor key, cursor id and row limit.
+    pub fn params<'a>(&'a self, leading: &[&'a (dyn ToSql + Sync)]) -> Vec<&'a (dyn ToSql + Sync)> {
+        let mut params = leading.to_vec();
+        params.extend_from_slice(&[&self.key, &self.id, &self.fetch]);
+        params
+    }
+
+    /// Drops the extra row and takes the cursor of the next page from the last item.
+    pub fn page(&self, mut rows: Vec<Row>) -> Page<Row> {
+        let next_cursor = if rows.len() > self.limit {
+            rows.truncate(self.limit);
+            rows.last().map(|row| {
+                Cursor {
+                    key: row.get("cursor_key"),
+                    id: row.get("cursor_id"),
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+        Page {
+            items: rows,
+            next_cursor,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Cursor {
+    key: String,
+    id: i64,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        base64::encode_config(format!("{}:{}", self.id, self.key), base64::URL_SAFE_NO_PAD)
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        let bytes = base64::decode_config(cursor, base64::URL_SAFE_NO_PAD).ok()?;
+        let text = String::from_utf8(bytes).ok()?;
+        let (id, key) = text.split_once(':')?;
+        Some(Self {
+            key: key.to_owned(),
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor {
+            key: "2021-05-03 10:11:12.5+00:feed".to_owned(),
+            id: 42,
+        };
+        assert_eq!(Cursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(Cursor::decode("not a cursor"), None);
+        assert_eq!(
+            Cursor::decode(&base64::encode_config("x:key", base64::URL_SAFE_NO_PAD)),
+            None
+        );
+    }
+}
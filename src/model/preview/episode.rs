@@ -8,7 +8,7 @@ use serde::Serializer;
 use std::convert::{TryFrom, TryInto};
 use std::str::FromStr;
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Episode {
     pub id: i64,
@@ -23,13 +23,8 @@ pub struct Episode {
     pub enclosure: MyEnclosure,
     pub explicit: bool,
     pub guid: Option<String>,
-}
-
-#[derive(Debug, serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct EpisodeNext {
-    pub items: Vec<Episode>,
-    pub offset: Option<i64>,
+    pub img: Option<Url>,
+    pub img_cache: Option<String>,
 }
 
 impl TryFrom<&rss::Enclosure> for MyEnclosure {
@@ -47,12 +42,13 @@ impl TryFrom<&rss::Enclosure> for MyEnclosure {
     }
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MyEnclosure {
     pub media_url: Url,
     pub length: i64,
     #[serde(serialize_with = "serialize_mime")]
+    #[schemars(with = "String")]
     pub mime_type: mime::Mime,
 }
 
@@ -72,6 +68,10 @@ impl Episode {
         self.enclosure.media_url.as_str()
     }
 
+    pub fn img_url(&self) -> Option<&str> {
+        self.img.as_ref().map(|url| url.as_str())
+    }
+
     pub fn from_items(items: &[rss::Item]) -> Vec<Episode> {
         let mut items: Vec<Episode> = items
             .iter()
@@ -110,7 +110,7 @@ impl<'a> TryFrom<&'a rss::Item> for Episode {
             keywords: item
                 .itunes_ext()
                 .and_then(|itunes| itunes.keywords())
-                .map(|k| k.split(',').map(|k| k.to_owned()).collect::<Vec<_>>()),
+                .and_then(parse_keywords),
             duration: item
                 .itunes_ext()
                 .and_then(|itunes| itunes.duration())
@@ -120,6 +120,11 @@ impl<'a> TryFrom<&'a rss::Item> for Episode {
             web_link: item.link().and_then(|u| Url::parse(u).ok()),
             explicit: parse_explicit(item.itunes_ext()),
             guid: item.guid().map(|guid| guid.value().to_string()),
+            img: item
+                .itunes_ext()
+                .and_then(|itunes| itunes.image())
+                .and_then(|u| Url::parse(u).ok()),
+            img_cache: None,
             enclosure: item
                 .enclosure()
                 .and_then(|en| en.try_into().ok())
@@ -153,10 +158,30 @@ impl From<tokio_postgres::Row> for Episode {
             published: row.get("published"),
             keywords: row.get("keywords"),
             guid: row.get("guid"),
+            img: row
+                .get::<_, Option<String>>("img")
+                .and_then(|url| Url::parse(&url).ok()),
+            img_cache: row.get("img_cache"),
         }
     }
 }
 
+pub fn normalize_keyword(keyword: &str) -> String {
+    keyword.trim().to_lowercase()
+}
+
+/// Splits `itunes:keywords` into sorted, lowercase tags without duplicates.
+fn parse_keywords(keywords: &str) -> Option<Vec<String>> {
+    let mut tags = keywords
+        .split(',')
+        .map(normalize_keyword)
+        .filter(|tag| !tag.is_empty())
+        .collect::<Vec<_>>();
+    tags.sort_unstable();
+    tags.dedup();
+    Some(tags).filter(|tags| !tags.is_empty())
+}
+
 fn parse_description(item: &rss::Item) -> Option<String> {
     let description = item
         .itunes_ext()
@@ -5,7 +5,7 @@ use std::collections::BTreeMap;
 
 use super::episode::Episode;
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FeedPreview<'a> {
     pub url: Url,
@@ -18,6 +18,10 @@ pub struct FeedPreview<'a> {
     pub language: Option<&'a str>,
     pub link_web: Option<Url>,
     pub categories: BTreeMap<&'a str, Vec<&'a str>>,
+    /// `<podcast:guid>`, stays the same when the show moves to another host.
+    pub podcast_guid: Option<&'a str>,
+    /// `<itunes:new-feed-url>`, where the show says it lives now.
+    pub new_feed_url: Option<Url>,
 }
 
 impl<'a> FeedPreview<'a> {
@@ -65,10 +69,25 @@ impl<'a> FeedPreview<'a> {
             subtitle: parse_subtitle(&feed),
             language: feed.language().map(|code| &code[..2]),
             categories: parse_categories(&feed),
+            podcast_guid: parse_podcast_guid(&feed),
+            new_feed_url: feed
+                .itunes_ext()
+                .and_then(|it| it.new_feed_url())
+                .and_then(|u| Url::parse(u).ok()),
         }
     }
 }
 
+fn parse_podcast_guid(feed: &'_ rss::Channel) -> Option<&str> {
+    feed.extensions()
+        .get("podcast")?
+        .get("guid")?
+        .first()?
+        .value()
+        .map(|guid| guid.trim())
+        .filter(|guid| !guid.is_empty())
+}
+
 fn parse_categories(feed: &'_ rss::Channel) -> BTreeMap<&str, Vec<&str>> {
     let mut categories_map = BTreeMap::new();
 
@@ -120,3 +139,38 @@ fn parse_subtitle(feed: &rss::Channel) -> Option<&str> {
 
     parsed_subtitle
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn channel(guid: Option<&str>) -> rss::Channel {
+        let guid = guid
+            .map(|guid| format!("<podcast:guid>{}</podcast:guid>", guid))
+            .unwrap_or_default();
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+<channel>
+    <title>Guid</title>
+    <link>https://example.com</link>
+    <description>Feed with a podcast:guid</description>
+    {}
+</channel>
+</rss>"#,
+            guid
+        );
+        rss::Channel::read_from(xml.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_podcast_guid() {
+        let with_guid = channel(Some(" 917393e3-1b1e-5cef-ace4-edaa54e1f810 "));
+        assert_eq!(
+            parse_podcast_guid(&with_guid),
+            Some("917393e3-1b1e-5cef-ace4-edaa54e1f810")
+        );
+        assert_eq!(parse_podcast_guid(&channel(Some("  "))), None);
+        assert_eq!(parse_podcast_guid(&channel(None)), None);
+    }
+}
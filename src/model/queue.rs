@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use postgres_types::{FromSql, ToSql};
+use serde::{Deserialize, Serialize};
+use tokio_pg_mapper_derive::PostgresMapper;
+
+use crate::time_date::serialize_datetime;
+
+#[derive(
+    Debug, ToSql, FromSql, Serialize, Deserialize, Clone, Copy, PartialEq, schemars::JsonSchema,
+)]
+#[postgres(name = "job_status")]
+pub enum JobStatus {
+    #[postgres(name = "queued")]
+    Queued,
+    #[postgres(name = "running")]
+    Running,
+    #[postgres(name = "done")]
+    Done,
+    #[postgres(name = "failed")]
+    Failed,
+}
+
+/// A row of the `job` table as shown to moderators.
+#[derive(Debug, PostgresMapper, Serialize, schemars::JsonSchema)]
+#[pg_mapper(table = "job")]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedJob {
+    pub id: i64,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub run_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub created: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub updated: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct JobFilter {
+    pub status: Option<JobStatus>,
+}
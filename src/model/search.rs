@@ -0,0 +1,91 @@
+use serde::Serialize;
+use tokio_pg_mapper_derive::PostgresMapper;
+use tokio_postgres::Row;
+
+use super::{feed::Feed, preview::episode::Episode};
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchPage {
+    pub items: Vec<Feed>,
+    pub total: i64,
+    pub offset: i64,
+    pub limit: i64,
+    pub facets: Facets,
+    /// Similar titles and author names, only filled when nothing matched.
+    pub did_you_mean: Vec<String>,
+}
+
+/// Hits per language and per category, each ignoring the filter on its own dimension.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Facets {
+    pub languages: Vec<Facet>,
+    pub categories: Vec<Facet>,
+}
+
+#[derive(Debug, Serialize, PostgresMapper, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[pg_mapper(table = "facet")]
+pub struct Facet {
+    pub id: i32,
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeHit {
+    #[serde(flatten)]
+    pub episode: Episode,
+    pub feed: ParentFeed,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<Highlight>,
+}
+
+/// Fields with the matched terms wrapped in the requested markers.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Highlight {
+    pub title: String,
+    pub description: Option<String>,
+    pub subtitle: Option<String>,
+}
+
+impl Highlight {
+    /// Present if the query selected the `hl_*` headline columns.
+    pub fn from_row(row: &Row) -> Option<Self> {
+        let title = row
+            .try_get::<_, Option<String>>("hl_title")
+            .ok()
+            .flatten()?;
+        Some(Self {
+            title,
+            description: row.try_get("hl_description").ok().flatten(),
+            subtitle: row.try_get("hl_subtitle").ok().flatten(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentFeed {
+    pub id: i32,
+    pub title: String,
+    pub img: Option<String>,
+}
+
+impl From<Row> for EpisodeHit {
+    fn from(row: Row) -> Self {
+        let feed = ParentFeed {
+            id: row.get("feed_id"),
+            title: row.get("feed_title"),
+            img: row.get("feed_img"),
+        };
+        Self {
+            highlight: Highlight::from_row(&row),
+            episode: Episode::from(row),
+            feed,
+        }
+    }
+}
@@ -4,9 +4,9 @@ use tokio_pg_mapper_derive::PostgresMapper;
 
 use crate::handler::auth::SESSION_KEY_ACCOUNT;
 
-use super::{feed::TinyFeed, Permission};
+use super::{Permission, Status};
 
-#[derive(Debug, PostgresMapper, Serialize, Deserialize, Clone)]
+#[derive(Debug, PostgresMapper, Serialize, Deserialize, Clone, schemars::JsonSchema)]
 #[pg_mapper(table = "account")]
 #[serde(rename_all = "camelCase")]
 pub struct Account {
@@ -18,7 +18,7 @@ pub struct Account {
     pub permission: Permission,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
 pub struct ShortAccount {
     pub id: i32,
     pub username: String,
@@ -45,10 +45,7 @@ impl Account {
     }
 }
 
-#[derive(Debug, Serialize)]
-pub struct SubmittedFeeds {
-    pub blocked: Vec<TinyFeed>,
-    pub online: Vec<TinyFeed>,
-    pub offline: Vec<TinyFeed>,
-    pub queued: Vec<TinyFeed>,
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SubmittedFilter {
+    pub status: Option<Status>,
 }
@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use postgres_types::{FromSql, ToSql};
+use serde::{Deserialize, Serialize};
+use tokio_pg_mapper_derive::PostgresMapper;
+use url::Url;
+
+use crate::time_date::{serialize_datetime, serialize_option_datetime};
+
+/// Catalogue changes a webhook can subscribe to.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, schemars::JsonSchema)]
+pub enum Event {
+    #[serde(rename = "feed.submitted")]
+    FeedSubmitted,
+    #[serde(rename = "feed.approved")]
+    FeedApproved,
+    #[serde(rename = "feed.blocked")]
+    FeedBlocked,
+    #[serde(rename = "episodes.ingested")]
+    EpisodesIngested,
+    #[serde(rename = "comment.created")]
+    CommentCreated,
+}
+
+impl Event {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Event::FeedSubmitted => "feed.submitted",
+            Event::FeedApproved => "feed.approved",
+            Event::FeedBlocked => "feed.blocked",
+            Event::EpisodesIngested => "episodes.ingested",
+            Event::CommentCreated => "comment.created",
+        }
+    }
+}
+
+#[derive(Debug, PostgresMapper, Serialize, schemars::JsonSchema)]
+#[pg_mapper(table = "webhook")]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: i32,
+    pub url: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub created: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewWebhook {
+    pub url: Url,
+    pub events: Vec<Event>,
+    /// Key of the `X-Elenco-Signature` HMAC, generated if missing.
+    pub secret: Option<String>,
+}
+
+/// A new webhook with the secret, which is not shown again.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(
+    Debug, ToSql, FromSql, Serialize, Deserialize, Clone, Copy, PartialEq, schemars::JsonSchema,
+)]
+#[postgres(name = "delivery_status")]
+pub enum DeliveryStatus {
+    #[postgres(name = "pending")]
+    Pending,
+    #[postgres(name = "delivered")]
+    Delivered,
+    #[postgres(name = "failed")]
+    Failed,
+}
+
+#[derive(Debug, PostgresMapper, Serialize, schemars::JsonSchema)]
+#[pg_mapper(table = "webhook_delivery")]
+#[serde(rename_all = "camelCase")]
+pub struct Delivery {
+    pub id: i64,
+    pub webhook_id: i32,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub created: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_option_datetime")]
+    pub delivered: Option<DateTime<Utc>>,
+}
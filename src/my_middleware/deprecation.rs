@@ -0,0 +1,88 @@
+//! Middleware for the unversioned routes, it marks every response as deprecated
+//! and links to the same path under the current API version.
+use std::{
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{self, HeaderName, HeaderValue, InvalidHeaderValue};
+use actix_web::Error;
+use futures_util::future::{ok, Future, Ready};
+
+use crate::routes::API_V1;
+
+#[derive(Clone)]
+pub struct Deprecated {
+    sunset: Option<HeaderValue>,
+}
+
+impl Deprecated {
+    /// `sunset` is the HTTP date after which the old routes are removed.
+    pub fn new(sunset: Option<&str>) -> Result<Self, InvalidHeaderValue> {
+        let sunset = sunset.map(HeaderValue::from_str).transpose()?;
+        Ok(Self { sunset })
+    }
+}
+
+impl<S> Transform<S, ServiceRequest> for Deprecated
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse;
+    type Error = Error;
+    type InitError = ();
+    type Transform = DeprecatedMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(DeprecatedMiddleware {
+            service: Rc::new(service),
+            sunset: self.sunset.clone(),
+        })
+    }
+}
+
+pub struct DeprecatedMiddleware<S> {
+    service: Rc<S>,
+    sunset: Option<HeaderValue>,
+}
+
+impl<S> Service<ServiceRequest> for DeprecatedMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse;
+    type Error = Error;
+    #[allow(clippy::type_complexity)]
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let successor = format!("<{}{}>; rel=\"successor-version\"", API_V1, req.path());
+        let sunset = self.sunset.clone();
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let mut res = fut.await?;
+            let headers = res.headers_mut();
+            headers.insert(
+                HeaderName::from_static("deprecation"),
+                HeaderValue::from_static("true"),
+            );
+            if let Some(sunset) = sunset {
+                headers.insert(HeaderName::from_static("sunset"), sunset);
+            }
+            if let Ok(link) = HeaderValue::from_str(&successor) {
+                headers.insert(header::LINK, link);
+            }
+            Ok(res)
+        })
+    }
+}
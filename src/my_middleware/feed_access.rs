@@ -71,7 +71,7 @@ where
                 Permission::User => {
                     if let Ok(feed_id) = &req.match_info().path()[1..].parse::<i32>() {
                         let submitter_check_stmnt = client
-                            .prepare(inc_sql!("get/feed/submitter_check"))
+                            .prepare_cached(inc_sql!("get/feed/submitter_check"))
                             .await
                             .map_err(log_error)?;
                         if client
@@ -1,3 +1,4 @@
 pub mod auth;
+pub mod deprecation;
 pub mod feed_access;
 pub mod moderator;
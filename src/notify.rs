@@ -0,0 +1,197 @@
+//! Live updates shared by all instances.
+//!
+//! New submissions and the progress of submission jobs are published with `NOTIFY`
+//! instead of straight into the broker, every instance listens on the channels and
+//! hands what arrives to its own `LiveFeedSocket`s and `JobSocket`s, so users see
+//! them behind a load balancer no matter which instance did the work. Catalogue
+//! changes are announced the same way so that every instance clears its `ResponseCache`.
+use actix_broker::{Broker, SystemBroker};
+use futures_util::{stream, StreamExt};
+use std::time::Duration;
+use tokio_postgres::AsyncMessage;
+
+use crate::{
+    cache::ResponseCache, db, handler::manage::ModeratorFeed, inc_sql, job::Job, socket::Message,
+    Client,
+};
+
+/// Channel of `sql/notify/moderator_feed.sql`.
+const MODERATOR_FEED: &str = "moderator_feed";
+/// Channel of `sql/notify/submission.sql`.
+const SUBMISSION: &str = "submission";
+/// Channel of `sql/notify/catalogue.sql`.
+const CATALOGUE: &str = "catalogue";
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Announces a submission to the moderators of all instances.
+/// Without the database it only reaches the sockets of this instance.
+pub async fn publish(client: &Client, feed: ModeratorFeed) {
+    let payload = serde_json::to_string(&feed).unwrap();
+    let result = match client
+        .prepare_cached(inc_sql!("notify/moderator_feed"))
+        .await
+    {
+        Ok(stmnt) => client.execute(&stmnt, &[&payload]).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = result {
+        log::error!("could not notify about feed {}: {}", feed.id, err);
+        Broker::<SystemBroker>::issue_async(Message::new(feed));
+    }
+}
+
+/// The job does not serialize its submitter, the sockets need it to pick their jobs.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JobUpdate {
+    user_id: i32,
+    job: Job,
+}
+
+/// Announces the progress of a submission job to the sockets of all instances.
+pub async fn publish_job(client: &Client, job: Job) {
+    let update = JobUpdate {
+        user_id: job.user_id,
+        job,
+    };
+    let payload = serde_json::to_string(&update).unwrap();
+    let result = match client.prepare_cached(inc_sql!("notify/submission")).await {
+        Ok(stmnt) => client.execute(&stmnt, &[&payload]).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = result {
+        log::error!("could not notify about job {}: {}", update.job.id, err);
+        Broker::<SystemBroker>::issue_async(update.into_job());
+    }
+}
+
+impl JobUpdate {
+    fn into_job(self) -> Job {
+        Job {
+            user_id: self.user_id,
+            ..self.job
+        }
+    }
+}
+
+/// Clears the cached catalogue responses here and on all other instances.
+/// Without the database the other instances expire theirs with the TTL.
+pub async fn invalidate_catalogue(client: &Client, cache: &ResponseCache) {
+    cache.invalidate_catalogue();
+    let result = match client.prepare_cached(inc_sql!("notify/catalogue")).await {
+        Ok(stmnt) => client.execute(&stmnt, &[]).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = result {
+        log::error!("could not notify about the catalogue: {}", err);
+    }
+}
+
+/// Spawns the listener on the current arbiter, it reconnects when the connection drops.
+pub fn start(cache: ResponseCache) {
+    actix_web::rt::spawn(async move {
+        loop {
+            if let Err(err) = listen(&cache).await {
+                log::error!("notification listener: {:#}", err);
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    });
+}
+
+async fn listen(cache: &ResponseCache) -> Result<(), anyhow::Error> {
+    let (client, mut connection) = db::util::connect().await?;
+    // notifications arrive on the connection, which has to be polled while the client is in use
+    let messages = stream::poll_fn(move |cx| connection.poll_message(cx));
+    let forwarding = actix_web::rt::spawn(forward(messages, cache.clone()));
+    client
+        .batch_execute(&format!(
+            "LISTEN {}; LISTEN {}; LISTEN {}",
+            MODERATOR_FEED, SUBMISSION, CATALOGUE
+        ))
+        .await?;
+    // changes made while the listener was down were not announced
+    cache.invalidate_catalogue();
+    log::info!(
+        "listening on {}, {} and {}",
+        MODERATOR_FEED,
+        SUBMISSION,
+        CATALOGUE
+    );
+    forwarding.await??;
+    Err(anyhow::anyhow!("listener connection closed"))
+}
+
+async fn forward(
+    messages: impl stream::Stream<Item = Result<AsyncMessage, tokio_postgres::Error>>,
+    cache: ResponseCache,
+) -> Result<(), tokio_postgres::Error> {
+    futures_util::pin_mut!(messages);
+    while let Some(message) = messages.next().await {
+        if let AsyncMessage::Notification(notification) = message? {
+            let payload = notification.payload();
+            match notification.channel() {
+                CATALOGUE => cache.invalidate_catalogue(),
+                SUBMISSION => match serde_json::from_str::<JobUpdate>(payload) {
+                    Ok(update) => Broker::<SystemBroker>::issue_async(update.into_job()),
+                    Err(err) => log::warn!("invalid job notification: {}", err),
+                },
+                _ => match decode(payload) {
+                    Ok(feed) => Broker::<SystemBroker>::issue_async(Message::new(feed)),
+                    Err(err) => log::warn!("invalid moderator feed notification: {}", err),
+                },
+            }
+        }
+    }
+    Ok(())
+}
+
+fn decode(payload: &str) -> Result<ModeratorFeed, serde_json::Error> {
+    serde_json::from_str(payload)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{job::Progress, model::Status};
+    use chrono::Utc;
+
+    #[test]
+    fn test_notification_round_trip() {
+        let now = Utc::now();
+        let feed = ModeratorFeed {
+            id: 3,
+            url: "https://example.com/feed.xml".to_string(),
+            title: "Example".to_string(),
+            author_name: "Someone".to_string(),
+            link_web: None,
+            status: Status::Queued,
+            submitted: now,
+            modified: now,
+            username: "user".to_string(),
+            reviewer_name: None,
+        };
+        let payload = serde_json::to_string(&feed).unwrap();
+        let decoded = decode(&payload).unwrap();
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), payload);
+        assert!(decode("{}").is_err());
+    }
+
+    #[test]
+    fn test_job_notification_keeps_submitter() {
+        let job = Job {
+            id: 5,
+            user_id: 7,
+            feed_url: url::Url::parse("https://example.com/feed.xml").unwrap(),
+            progress: Progress::Fetched,
+            updated: Utc::now(),
+            feed_id: None,
+        };
+        let payload = serde_json::to_string(&JobUpdate { user_id: 7, job }).unwrap();
+        let job = serde_json::from_str::<JobUpdate>(&payload)
+            .unwrap()
+            .into_job();
+        assert_eq!(job.id, 5);
+        assert_eq!(job.user_id, 7);
+    }
+}
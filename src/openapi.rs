@@ -0,0 +1,482 @@
+//! OpenAPI 3 document of the HTTP API, served at `/openapi.json`.
+//!
+//! The schemas are derived from the request and response types with `schemars`,
+//! the operations mirror `routes::endpoints` and a test keeps both in sync.
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::Schema,
+    JsonSchema,
+};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+use crate::{
+    auth::{login::LoginForm, register::RegisterForm},
+    cache::CacheStats,
+    handler::{
+        episode::EpisodeSearchQuery,
+        error::JsonError,
+        feed::SearchQuery,
+        manage::{AssignPayload, ModeratorFeed, ReviewPayload},
+        save_preview_feed::{preview::PreviewJson, FeedForm},
+        subscription::SubscribePayload,
+        tag::TagQuery,
+        user::UpdatePayload,
+    },
+    job::Job,
+    model::{
+        category::Category,
+        feed::{Feed, FeedUserMeta, Image, TinyFeed},
+        page::{Page, PageQuery},
+        preview::episode::Episode,
+        queue::{JobFilter, QueuedJob},
+        search::{EpisodeHit, SearchPage},
+        user::{Account, SubmittedFilter},
+        webhook::{CreatedWebhook, Delivery, NewWebhook, Webhook},
+        Author, Comment, Meta, NewComment, Suggestion, Tag,
+    },
+    refresh::ArtworkUpdate,
+    routes::{Access, API_V1},
+};
+
+/// Session cookie set by `/auth/login`.
+const SESSION_SCHEME: &str = "session";
+
+struct Operation {
+    method: &'static str,
+    path: &'static str,
+    summary: &'static str,
+    access: Access,
+    integer_path: bool,
+    query: Vec<Value>,
+    body: Option<(&'static str, Schema)>,
+    status: u16,
+    response: Option<Schema>,
+}
+
+impl Operation {
+    fn new(method: &'static str, path: &'static str, summary: &'static str) -> Self {
+        Self {
+            method,
+            path,
+            summary,
+            access: Access::Public,
+            integer_path: false,
+            query: Vec::new(),
+            body: None,
+            status: 200,
+            response: None,
+        }
+    }
+
+    fn get(path: &'static str, summary: &'static str) -> Self {
+        Self::new("get", path, summary)
+    }
+
+    fn post(path: &'static str, summary: &'static str) -> Self {
+        Self::new("post", path, summary)
+    }
+
+    fn patch(path: &'static str, summary: &'static str) -> Self {
+        Self::new("patch", path, summary)
+    }
+
+    fn delete(path: &'static str, summary: &'static str) -> Self {
+        Self::new("delete", path, summary)
+    }
+
+    fn login(mut self) -> Self {
+        self.access = Access::Login;
+        self
+    }
+
+    fn moderator(mut self) -> Self {
+        self.access = Access::Moderator;
+        self
+    }
+
+    /// Path parameters are ids instead of names.
+    fn integer_path(mut self) -> Self {
+        self.integer_path = true;
+        self
+    }
+
+    fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    fn query<T: JsonSchema>(mut self, gen: &mut SchemaGenerator) -> Self {
+        let root = gen.root_schema_for::<T>();
+        let object = root.schema.object.unwrap_or_default();
+        self.query
+            .extend(object.properties.iter().map(|(name, schema)| {
+                json!({
+                    "name": name,
+                    "in": "query",
+                    "required": object.required.contains(name),
+                    "schema": schema,
+                })
+            }));
+        self
+    }
+
+    fn json<T: JsonSchema>(mut self, gen: &mut SchemaGenerator) -> Self {
+        self.body = Some(("application/json", gen.subschema_for::<T>()));
+        self
+    }
+
+    fn form<T: JsonSchema>(mut self, gen: &mut SchemaGenerator) -> Self {
+        self.body = Some((
+            "application/x-www-form-urlencoded",
+            gen.subschema_for::<T>(),
+        ));
+        self
+    }
+
+    fn returns<T: JsonSchema>(mut self, gen: &mut SchemaGenerator) -> Self {
+        self.response = Some(gen.subschema_for::<T>());
+        self
+    }
+
+    fn path_params(&self) -> Vec<Value> {
+        let kind = if self.integer_path {
+            "integer"
+        } else {
+            "string"
+        };
+        self.path
+            .split('/')
+            .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": kind },
+                })
+            })
+            .collect()
+    }
+
+    fn to_json(&self) -> Value {
+        let error = json!({ "$ref": "#/components/responses/Error" });
+        let mut responses = Map::new();
+        let success = match &self.response {
+            Some(schema) => json!({
+                "description": "OK",
+                "content": { "application/json": { "schema": schema } },
+            }),
+            None => json!({ "description": "OK" }),
+        };
+        responses.insert(self.status.to_string(), success);
+        responses.insert("400".into(), error.clone());
+        if self.access != Access::Public {
+            responses.insert("401".into(), error.clone());
+        }
+        if self.access == Access::Moderator {
+            responses.insert("403".into(), error.clone());
+        }
+        if self.path.contains('{') {
+            responses.insert("404".into(), error.clone());
+        }
+        responses.insert("500".into(), error);
+
+        let mut parameters = self.path_params();
+        parameters.extend(self.query.iter().cloned());
+        let mut operation = json!({
+            "summary": self.summary,
+            "tags": [self.path.split('/').nth(1).unwrap_or_default()],
+            "parameters": parameters,
+            "responses": responses,
+        });
+        if let Some((content_type, schema)) = &self.body {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { (*content_type): { "schema": schema } },
+            });
+        }
+        if self.access != Access::Public {
+            operation["security"] = json!([{ SESSION_SCHEME: [] }]);
+        }
+        if self.access == Access::Moderator {
+            operation["description"] = json!("Requires a moderator account.");
+        }
+        operation
+    }
+}
+
+fn operations(gen: &mut SchemaGenerator) -> Vec<Operation> {
+    vec![
+        Operation::get("/openapi.json", "This document"),
+        Operation::get(
+            "/completion/{query}",
+            "Autocomplete feeds, authors, categories and episodes",
+        )
+        .returns::<Vec<Suggestion>>(gen),
+        Operation::get("/meta", "Catalogue statistics").returns::<Meta>(gen),
+        Operation::get("/feeds/top", "Most subscribed feeds")
+            .query::<PageQuery>(gen)
+            .returns::<Page<TinyFeed>>(gen),
+        Operation::get("/feeds/recent", "Recently approved feeds")
+            .query::<PageQuery>(gen)
+            .returns::<Page<TinyFeed>>(gen),
+        Operation::get("/feeds/search", "Full text search over feeds")
+            .query::<SearchQuery>(gen)
+            .returns::<SearchPage>(gen),
+        Operation::get(
+            "/feed/{id}",
+            "Feed by id or title with its first episodes, a shared title resolves to the oldest feed",
+        )
+        .returns::<Feed>(gen),
+        Operation::get(
+            "/feed/{id}/related",
+            "A few random feeds sharing a category, not paged",
+        )
+        .integer_path()
+        .returns::<Vec<TinyFeed>>(gen),
+        Operation::post("/feed/preview", "Parse a feed without saving it")
+            .login()
+            .json::<FeedForm>(gen)
+            .returns::<PreviewJson<'static>>(gen),
+        Operation::post("/feed/new", "Queue a feed submission for review")
+            .login()
+            .status(202)
+            .json::<FeedForm>(gen)
+            .returns::<Job>(gen),
+        Operation::patch("/feed/update", "Change the status of an own feed")
+            .login()
+            .json::<UpdatePayload>(gen),
+        Operation::get("/episode/{id}", "Episode by id")
+            .integer_path()
+            .returns::<Episode>(gen),
+        Operation::get("/episodes/search", "Full text search over episodes")
+            .query::<EpisodeSearchQuery>(gen)
+            .query::<PageQuery>(gen)
+            .returns::<Page<EpisodeHit>>(gen),
+        Operation::get("/episodes/{feed_id}", "Episodes of a feed, newest first")
+            .integer_path()
+            .query::<PageQuery>(gen)
+            .returns::<Page<Episode>>(gen),
+        Operation::get("/img-path/{feed_title}", "Cached artwork of a feed").returns::<Image>(gen),
+        Operation::post("/subscription", "Subscribe to a feed")
+            .login()
+            .status(201)
+            .json::<SubscribePayload>(gen),
+        Operation::delete("/subscription", "Unsubscribe from a feed")
+            .login()
+            .json::<SubscribePayload>(gen),
+        Operation::get("/subscription/user", "Feeds the user subscribed to")
+            .login()
+            .query::<PageQuery>(gen)
+            .returns::<Page<TinyFeed>>(gen),
+        Operation::post("/subscription/user", "Subscription and ownership of a feed")
+            .login()
+            .json::<SubscribePayload>(gen)
+            .returns::<FeedUserMeta>(gen),
+        Operation::get("/categories", "All categories with subcategories")
+            .returns::<Vec<Category>>(gen),
+        Operation::get("/category/{category}", "Category by id or name").returns::<Category>(gen),
+        Operation::get("/category/{category}/feeds", "Feeds of a category")
+            .query::<PageQuery>(gen)
+            .returns::<Page<Feed>>(gen),
+        Operation::get("/tags", "Most used episode keywords")
+            .query::<TagQuery>(gen)
+            .returns::<Vec<Tag>>(gen),
+        Operation::get("/tag/{name}/episodes", "Episodes with a keyword")
+            .query::<PageQuery>(gen)
+            .returns::<Page<EpisodeHit>>(gen),
+        Operation::get(
+            "/tag/{name}/feeds",
+            "Feeds with episodes carrying a keyword",
+        )
+        .query::<PageQuery>(gen)
+        .returns::<Page<Feed>>(gen),
+        Operation::get("/authors", "Authors with online feeds")
+            .query::<PageQuery>(gen)
+            .returns::<Page<Author>>(gen),
+        Operation::get(
+            "/author/{author_id_name}/feeds",
+            "Feeds of an author by id or name",
+        )
+        .query::<PageQuery>(gen)
+        .returns::<Page<Feed>>(gen),
+        Operation::post("/comment", "Comment on a feed")
+            .login()
+            .json::<NewComment>(gen)
+            .returns::<Comment>(gen),
+        Operation::get("/comments/{id}", "Comments of a feed, newest first")
+            .integer_path()
+            .query::<PageQuery>(gen)
+            .returns::<Page<Comment>>(gen),
+        Operation::post("/auth/register", "Create an account").json::<RegisterForm>(gen),
+        Operation::post("/auth/login", "Start a session")
+            .json::<LoginForm>(gen)
+            .returns::<Account>(gen),
+        Operation::post("/auth/logout", "End the session").login(),
+        Operation::get("/user/info", "Account of the session")
+            .login()
+            .returns::<Account>(gen),
+        Operation::get("/user/feeds", "Feeds submitted by the user, newest first")
+            .login()
+            .query::<SubmittedFilter>(gen)
+            .query::<PageQuery>(gen)
+            .returns::<Page<TinyFeed>>(gen),
+        Operation::get("/user/job/{id}", "Progress of a feed submission")
+            .login()
+            .integer_path()
+            .returns::<Job>(gen),
+        Operation::get(
+            "/user/socket/jobs",
+            "WebSocket pushing the progress of the user's submissions",
+        )
+        .login()
+        .status(101),
+        Operation::get("/admin/review/unassigned", "Feeds waiting for a reviewer")
+            .moderator()
+            .returns::<Vec<ModeratorFeed>>(gen),
+        Operation::get("/admin/review/inbox", "Feeds assigned to the moderator")
+            .moderator()
+            .returns::<Vec<ModeratorFeed>>(gen),
+        Operation::patch("/admin/review/assign", "Assign feeds to the moderator")
+            .moderator()
+            .json::<AssignPayload>(gen),
+        Operation::get("/admin/review/reviewed", "Reviewed feeds")
+            .moderator()
+            .returns::<Vec<ModeratorFeed>>(gen),
+        Operation::patch("/admin/review", "Set the status of reviewed feeds")
+            .moderator()
+            .json::<ReviewPayload>(gen),
+        Operation::patch(
+            "/admin/feed/{id}/artwork",
+            "Download the channel image again",
+        )
+        .moderator()
+        .integer_path()
+        .returns::<ArtworkUpdate>(gen),
+        Operation::get("/admin/jobs", "Jobs of the background queue")
+            .moderator()
+            .query::<JobFilter>(gen)
+            .query::<PageQuery>(gen)
+            .returns::<Page<QueuedJob>>(gen),
+        Operation::patch("/admin/job/{id}/retry", "Queue a failed job again")
+            .moderator()
+            .integer_path()
+            .returns::<QueuedJob>(gen),
+        Operation::get("/admin/webhooks", "Registered webhooks")
+            .moderator()
+            .returns::<Vec<Webhook>>(gen),
+        Operation::post(
+            "/admin/webhooks",
+            "Register a webhook, the response contains its signing secret",
+        )
+        .moderator()
+        .status(201)
+        .json::<NewWebhook>(gen)
+        .returns::<CreatedWebhook>(gen),
+        Operation::delete("/admin/webhook/{id}", "Remove a webhook")
+            .moderator()
+            .integer_path()
+            .status(204),
+        Operation::get(
+            "/admin/webhook/{id}/deliveries",
+            "Delivery log of a webhook",
+        )
+        .moderator()
+        .integer_path()
+        .query::<PageQuery>(gen)
+        .returns::<Page<Delivery>>(gen),
+        Operation::get("/admin/cache", "Hit and miss counts of the response caches")
+            .moderator()
+            .returns::<BTreeMap<String, CacheStats>>(gen),
+        Operation::get(
+            "/admin/socket/unassigned",
+            "WebSocket announcing new submissions",
+        )
+        .moderator()
+        .status(101),
+        Operation::post("/admin/register", "Create a moderator account")
+            .moderator()
+            .status(302)
+            .form::<RegisterForm>(gen),
+    ]
+}
+
+pub fn spec() -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+    let operations = operations(&mut gen);
+    let error = gen.subschema_for::<JsonError>();
+
+    let mut paths = Map::new();
+    for operation in &operations {
+        let item = paths
+            .entry(operation.path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        item[operation.method] = operation.to_json();
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "elenco",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "servers": [{ "url": API_V1 }],
+        "paths": paths,
+        "components": {
+            "schemas": gen.take_definitions(),
+            "responses": {
+                "Error": {
+                    "description": "Error",
+                    "content": { "application/json": { "schema": error } },
+                },
+            },
+            "securitySchemes": {
+                SESSION_SCHEME: { "type": "apiKey", "in": "cookie", "name": "auth" },
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_every_route_is_documented() {
+        let mut gen = SchemaSettings::openapi3().into_generator();
+        let documented = operations(&mut gen)
+            .into_iter()
+            .map(|op| (op.method.to_string(), op.path.to_string(), op.access))
+            .collect::<Vec<_>>();
+        let registered = crate::routes::endpoints()
+            .into_iter()
+            .map(|e| {
+                (
+                    e.method.as_str().to_lowercase(),
+                    e.path.to_string(),
+                    e.access,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let missing = registered
+            .iter()
+            .filter(|route| !documented.contains(route))
+            .collect::<Vec<_>>();
+        assert!(
+            missing.is_empty(),
+            "routes missing from the spec or documented with other access: {:?}",
+            missing
+        );
+        let unknown = documented
+            .iter()
+            .filter(|op| !registered.contains(op))
+            .collect::<Vec<_>>();
+        assert!(
+            unknown.is_empty(),
+            "spec documents unknown routes: {:?}",
+            unknown
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Durable background work on top of the `job` table.
+//!
+//! Workers claim due jobs with `FOR UPDATE SKIP LOCKED`, so any number of them,
+//! in this process or another one, can poll the table without running a job twice.
+//! A failing job is retried with exponential backoff until `max_attempts` is used up,
+//! afterwards it stays `failed` until a moderator retries it at `/admin/job/{id}/retry`.
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+use crate::{
+    db::{self, queue::Claimed},
+    handler::save_preview_feed::save,
+    notify, refresh, webhook, Client, State,
+};
+
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// A job running for longer than this is assumed to be lost with its worker.
+const JOB_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+const BACKOFF_BASE: Duration = Duration::from_secs(30);
+const BACKOFF_MAX: Duration = Duration::from_secs(60 * 60);
+
+/// Everything a worker knows how to do, stored as `kind` and `payload` of a job row.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+pub enum Task {
+    /// Downloads the channel image of a feed again, see [`refresh::feed_artwork`].
+    #[serde(rename_all = "camelCase")]
+    RefreshArtwork { feed_id: i32, force: bool },
+    /// Sends a logged webhook delivery, see [`webhook::deliver`].
+    #[serde(rename_all = "camelCase")]
+    DeliverWebhook { delivery_id: i64 },
+    /// Computes blurhash and colour of an image cached before they were stored.
+    #[serde(rename_all = "camelCase")]
+    ImagePlaceholder { img_id: i32 },
+    /// Fetches and stores a submitted feed, see [`save::run`].
+    #[serde(rename_all = "camelCase")]
+    SubmitFeed { submission_id: i64 },
+}
+
+impl Task {
+    fn to_row(&self) -> (String, serde_json::Value) {
+        let mut value = serde_json::to_value(self).unwrap();
+        let kind = value["kind"].as_str().unwrap_or_default().to_string();
+        (kind, value["payload"].take())
+    }
+
+    fn from_row(kind: &str, payload: serde_json::Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(serde_json::json!({ "kind": kind, "payload": payload }))
+    }
+
+    /// `last_attempt` is set when a failure will not be retried.
+    async fn run(self, state: &State, last_attempt: bool) -> Result<(), anyhow::Error> {
+        match self {
+            Task::RefreshArtwork { feed_id, force } => {
+                let mut client = state.db_pool.get().await?;
+                let update =
+                    refresh::feed_artwork(&mut client, &state.img_cache, feed_id, force).await?;
+                if update.changed {
+                    notify::invalidate_catalogue(&client, &state.cache).await;
+                }
+            }
+            Task::DeliverWebhook { delivery_id } => {
+                webhook::deliver(state, delivery_id, last_attempt).await?
+            }
+            Task::ImagePlaceholder { img_id } => {
+                let client = state.db_pool.get().await?;
+                if let Some(file_name) = db::img_without_placeholder(&client, img_id).await? {
+                    match state.img_cache.placeholder(&file_name).await? {
+                        Some((blurhash, color)) => {
+                            db::update_img_placeholder(&client, img_id, &blurhash, &color).await?;
+                            notify::invalidate_catalogue(&client, &state.cache).await;
+                        }
+                        None => log::warn!("img {} has no placeholder", img_id),
+                    }
+                }
+            }
+            Task::SubmitFeed { submission_id } => {
+                save::run(state, submission_id, last_attempt).await?
+            }
+        }
+        Ok(())
+    }
+}
+
+pub async fn enqueue(client: &Client, task: &Task) -> Result<i64, tokio_postgres::Error> {
+    schedule(client, task, Utc::now()).await
+}
+
+/// Queues `task` to run no earlier than `run_at`.
+pub async fn schedule(
+    client: &Client,
+    task: &Task,
+    run_at: DateTime<Utc>,
+) -> Result<i64, tokio_postgres::Error> {
+    let (kind, payload) = task.to_row();
+    db::queue::insert(client, &kind, &payload, run_at, DEFAULT_MAX_ATTEMPTS).await
+}
+
+/// Delay before the next attempt, doubling with every failed one.
+pub fn backoff(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    BACKOFF_BASE
+        .checked_mul(2u32.pow(exponent))
+        .map_or(BACKOFF_MAX, |delay| delay.min(BACKOFF_MAX))
+}
+
+/// Spawns `workers` polling loops on the current arbiter.
+pub fn start(state: State, workers: usize) {
+    for worker in 0..workers {
+        actix_web::rt::spawn(work(state.clone(), worker));
+    }
+    log::info!("started {} job queue workers", workers);
+}
+
+async fn work(state: State, worker: usize) {
+    loop {
+        match next(&state).await {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(err) => log::error!("job queue worker {}: {:?}", worker, err),
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Runs the next due job, `false` if there was none.
+async fn next(state: &State) -> Result<bool, anyhow::Error> {
+    let client = state.db_pool.get().await?;
+    let job = match db::queue::claim(&client, JOB_TIMEOUT).await? {
+        Some(job) => job,
+        None => return Ok(false),
+    };
+    // the connection goes back to the pool while the task runs
+    drop(client);
+    let Claimed {
+        id,
+        kind,
+        payload,
+        attempts,
+        max_attempts,
+    } = job;
+
+    let result = match Task::from_row(&kind, payload) {
+        Ok(task) => task.run(state, attempts >= max_attempts).await,
+        Err(err) => Err(anyhow::anyhow!("unknown job {}: {}", kind, err)),
+    };
+    let client = state.db_pool.get().await?;
+    match result {
+        Ok(()) => db::queue::done(&client, id).await?,
+        Err(err) => {
+            log::warn!(
+                "job {} ({}) failed, attempt {}/{}: {:?}",
+                id,
+                kind,
+                attempts,
+                max_attempts,
+                err
+            );
+            db::queue::failed(&client, id, &format!("{:#}", err), backoff(attempts)).await?
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        assert_eq!(backoff(1), BACKOFF_BASE);
+        assert_eq!(backoff(2), BACKOFF_BASE * 2);
+        assert_eq!(backoff(4), BACKOFF_BASE * 8);
+        assert_eq!(backoff(0), BACKOFF_BASE);
+        assert_eq!(backoff(100), BACKOFF_MAX);
+    }
+
+    #[test]
+    fn test_task_row_round_trip() {
+        let task = Task::RefreshArtwork {
+            feed_id: 7,
+            force: true,
+        };
+        let (kind, payload) = task.to_row();
+        assert_eq!(kind, "refresh_artwork");
+        assert_eq!(payload, serde_json::json!({ "feedId": 7, "force": true }));
+        assert_eq!(Task::from_row(&kind, payload).unwrap(), task);
+        let task = Task::DeliverWebhook { delivery_id: 9 };
+        let (kind, payload) = task.to_row();
+        assert_eq!(kind, "deliver_webhook");
+        assert_eq!(payload, serde_json::json!({ "deliveryId": 9 }));
+        let task = Task::SubmitFeed { submission_id: 3 };
+        let (kind, payload) = task.to_row();
+        assert_eq!(kind, "submit_feed");
+        assert_eq!(payload, serde_json::json!({ "submissionId": 3 }));
+        assert!(Task::from_row("send_mail", serde_json::json!({})).is_err());
+    }
+}
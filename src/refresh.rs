@@ -0,0 +1,130 @@
+//! Keeps saved feeds in sync with changes made to their RSS source after submission.
+use chrono::Utc;
+use reqwest::Url;
+use std::time::Duration;
+
+use crate::{
+    db::{self, feed::FeedImg},
+    handler::{error::ApiError, save_preview_feed::fetch},
+    img_cache::ImageCache,
+    model::preview::feed::FeedPreview,
+    queue::{self, Task},
+    Client, State,
+};
+
+/// How often the artwork of an online feed is compared with its RSS source.
+const ARTWORK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// How often the due feeds are looked up.
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtworkUpdate {
+    pub feed_id: i32,
+    pub changed: bool,
+    pub released: Option<String>,
+}
+
+/// Fetches the feed again and switches its artwork if the channel image has changed.
+/// With `force` the image is downloaded and linked even if the URL did not change.
+pub async fn feed_artwork(
+    client: &mut Client,
+    img_cache: &ImageCache,
+    feed_id: i32,
+    force: bool,
+) -> Result<ArtworkUpdate, ApiError> {
+    let current = db::feed::img_link(client, feed_id)
+        .await
+        .map_err(|_e| ApiError::FeedByIdNotFound(feed_id))?;
+    let url = Url::parse(&current.url)?;
+    let bytes = fetch(&url)
+        .await
+        .map_err(|err| ApiError::Internal(err.into()))?;
+    let channel = rss::Channel::read_from(std::io::Cursor::new(&bytes))
+        .map_err(|err| ApiError::Internal(err.into()))?;
+    let preview = FeedPreview::parse(&channel, url);
+    artwork(
+        client,
+        img_cache,
+        feed_id,
+        &current,
+        preview.img.as_ref(),
+        force,
+    )
+    .await
+}
+
+pub async fn artwork(
+    client: &mut Client,
+    img_cache: &ImageCache,
+    feed_id: i32,
+    current: &FeedImg,
+    channel_img: Option<&Url>,
+    force: bool,
+) -> Result<ArtworkUpdate, ApiError> {
+    let img_url = match channel_img {
+        Some(url) if force || current.link.as_deref() != Some(url.as_str()) => url,
+        _ => {
+            return Ok(ArtworkUpdate {
+                feed_id,
+                changed: false,
+                released: None,
+            })
+        }
+    };
+
+    let img = img_cache.clone().download(img_url).await?;
+    let (img_id, released) = db::feed::switch_img(client, feed_id, current.img_id, &img)
+        .await
+        .map_err(|err| ApiError::Internal(err.into()))?;
+    // a new URL for the same bytes only updates the link
+    let changed = current.img_id != Some(img_id);
+    if let Some(file_name) = &released {
+        if let Err(err) = img_cache.remove(file_name).await {
+            log::warn!("could not remove released img {}: {:?}", file_name, err);
+        }
+    }
+    if changed {
+        log::info!("artwork of feed {} switched to {}", feed_id, img.file_name);
+    }
+
+    Ok(ArtworkUpdate {
+        feed_id,
+        changed,
+        released,
+    })
+}
+
+/// Spawns the loop that queues a refresh without `force` for every online feed
+/// once per `ARTWORK_INTERVAL`, so artwork changed at the source is picked up.
+pub fn start(state: State) {
+    actix_web::rt::spawn(async move {
+        loop {
+            if let Err(err) = schedule_artwork(&state).await {
+                log::error!("could not schedule artwork refreshes: {:#}", err);
+            }
+            tokio::time::sleep(SCHEDULE_INTERVAL).await;
+        }
+    });
+}
+
+/// Queues the due feeds spread over the next `SCHEDULE_INTERVAL`,
+/// so their hosts are not asked all at once.
+async fn schedule_artwork(state: &State) -> Result<(), anyhow::Error> {
+    let client = state.db_pool.get().await?;
+    let due = db::feed::artwork_refresh_due(&client, ARTWORK_INTERVAL).await?;
+    let spread = SCHEDULE_INTERVAL / due.len().max(1) as u32;
+    let now = Utc::now();
+    for (i, feed_id) in due.iter().enumerate() {
+        let task = Task::RefreshArtwork {
+            feed_id: *feed_id,
+            force: false,
+        };
+        let run_at = now + chrono::Duration::from_std(spread * i as u32)?;
+        queue::schedule(&client, &task, run_at).await?;
+    }
+    if !due.is_empty() {
+        log::info!("scheduled the artwork refresh of {} feeds", due.len());
+    }
+    Ok(())
+}
@@ -2,138 +2,190 @@ use crate::{
     handler::{self, user},
     my_middleware,
 };
-use actix_web::web::{self};
+use actix_web::{
+    guard,
+    http::Method,
+    web::{self, Route},
+};
 use handler::{auth, save_preview_feed};
 
-pub fn user(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/user")
-            .wrap(my_middleware::auth::CheckLogin)
-            .route("/info", web::get().to(auth::user_info))
-            .route("/feeds", web::get().to(user::submitted_feeds)),
-    );
+/// Prefix of the current API version, the unversioned routes are deprecated aliases.
+pub const API_V1: &str = "/api/v1";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Access {
+    Public,
+    /// Behind `CheckLogin`.
+    Login,
+    /// Behind `CheckLogin` and `Moderator`.
+    Moderator,
 }
 
-pub fn auth(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/auth")
-            .route("/register", web::post().to(auth::register))
-            .route("/login", web::post().to(auth::login))
-            .service(
-                web::scope("/")
-                    .route("logout", web::post().to(auth::logout))
-                    .wrap(my_middleware::auth::CheckLogin),
-            ),
-    );
+/// One route of the API, `configure` registers them and the OpenAPI test
+/// compares them with the documented operations.
+pub struct Endpoint {
+    pub method: Method,
+    pub path: &'static str,
+    pub access: Access,
+    to: fn(Route) -> Route,
 }
 
-pub fn moderator(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/admin")
-            .wrap(my_middleware::auth::CheckLogin)
-            .wrap(my_middleware::moderator::Moderator)
-            .service(
-                web::scope("/review")
-                    .route(
-                        "/unassigned",
-                        web::get().to(handler::manage::all_unassigned),
-                    )
-                    .route("/inbox", web::get().to(handler::manage::reviewer_inbox))
-                    .route(
-                        "/assign",
-                        web::patch().to(handler::manage::assign_for_review),
-                    )
-                    .route("/reviewed", web::get().to(handler::manage::reviewed))
-                    .route("", web::patch().to(handler::manage::review_feed)),
-            )
-            .service(web::scope("/socket").route(
-                "/unassigned",
-                web::get().to(handler::manage::register_socket),
-            ))
-            .route(
-                "/register",
-                web::post().to(handler::manage::register_moderator),
-            ),
-    );
+impl Endpoint {
+    fn new(method: Method, path: &'static str, to: fn(Route) -> Route) -> Self {
+        Self {
+            method,
+            path,
+            access: Access::Public,
+            to,
+        }
+    }
+
+    fn get(path: &'static str, to: fn(Route) -> Route) -> Self {
+        Self::new(Method::GET, path, to)
+    }
+
+    fn post(path: &'static str, to: fn(Route) -> Route) -> Self {
+        Self::new(Method::POST, path, to)
+    }
+
+    fn patch(path: &'static str, to: fn(Route) -> Route) -> Self {
+        Self::new(Method::PATCH, path, to)
+    }
+
+    fn delete(path: &'static str, to: fn(Route) -> Route) -> Self {
+        Self::new(Method::DELETE, path, to)
+    }
+
+    fn login(mut self) -> Self {
+        self.access = Access::Login;
+        self
+    }
+
+    fn moderator(mut self) -> Self {
+        self.access = Access::Moderator;
+        self
+    }
 }
 
-pub fn api(cfg: &mut web::ServiceConfig) {
-    cfg.route(
-        "/completion/{query}",
-        web::route().to(handler::feed::completion),
-    )
-    .route("/meta", web::get().to(handler::meta))
-    .service(
-        web::scope("/feeds")
-            .route("/top", web::get().to(handler::feed::charts))
-            .route("/recent", web::get().to(handler::feed::recent))
-            .route("/search", web::get().to(handler::feed::search)),
-    )
-    .service(
-        web::scope("/feed")
-            .route("/{id}", web::get().to(handler::feed::by_name_or_id))
-            .route("/{id}/related", web::get().to(handler::feed::related))
-            .service(
-                web::scope("/") // change / to action
+/// Registers every endpoint, each on its own resource guarded by the method
+/// so that endpoints sharing a path fall through to each other.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    for endpoint in endpoints() {
+        let resource = web::resource(endpoint.path)
+            .guard(guard::Method(endpoint.method.clone()))
+            .route((endpoint.to)(web::route()));
+        match endpoint.access {
+            Access::Public => cfg.service(resource),
+            Access::Login => cfg.service(resource.wrap(my_middleware::auth::CheckLogin)),
+            Access::Moderator => cfg.service(
+                resource
                     .wrap(my_middleware::auth::CheckLogin)
-                    .route(
-                        "preview",
-                        web::post().to(save_preview_feed::preview::create),
-                    )
-                    .route("new", web::post().to(save_preview_feed::save::save))
-                    .route("update", web::patch().to(user::update_feed)),
-            ),
-    )
-    .route(
-        "/episode/{id}",
-        web::get().to(handler::episode::by_episode_id),
-    )
-    .route(
-        "/episodes/{feed_id}",
-        web::get().to(handler::episode::by_feed_id),
-    )
-    .route(
-        "/img-path/{feed_title}",
-        web::get().to(handler::image_for_feed),
-    )
-    .service(
-        web::scope("/subscription")
-            .wrap(my_middleware::auth::CheckLogin)
-            .service(
-                web::resource("")
-                    .route(web::post().to(handler::subscription::subscribe))
-                    .route(web::delete().to(handler::subscription::unsubscribe)),
-            )
-            .service(
-                web::resource("/user")
-                    .route(web::get().to(user::subscriptions))
-                    .route(web::post().to(handler::subscription::subscription_info)),
+                    .wrap(my_middleware::moderator::Moderator),
             ),
-    )
-    .route("/categories", web::get().to(handler::category::all))
-    .service(
-        web::scope("/category")
-            .route(
-                "/{category}",
-                web::get().to(handler::category::by_id_or_name),
-            )
-            .route(
-                "/{category}/feeds",
-                web::get().to(handler::feed::by_category),
-            ),
-    )
-    .route("/authors", web::get().to(handler::author::all))
-    .route(
-        "/author/{author_id_name}/feeds",
-        web::get().to(handler::author::feeds),
-    )
-    .service(
-        web::resource("/comment")
-            .wrap(my_middleware::auth::CheckLogin)
-            .route(web::post().to(handler::comment::new)),
-    )
-    .route(
-        "/comments/{id}",
-        web::get().to(handler::comment::get_for_feed),
-    );
+        };
+    }
+}
+
+/// Routes are matched in this order, fixed paths go before the parameters they shadow.
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint::get("/completion/{query}", |r| r.to(handler::feed::completion)),
+        Endpoint::get("/meta", |r| r.to(handler::meta)),
+        Endpoint::get("/openapi.json", |r| r.to(handler::openapi)),
+        Endpoint::get("/feeds/top", |r| r.to(handler::feed::charts)),
+        Endpoint::get("/feeds/recent", |r| r.to(handler::feed::recent)),
+        Endpoint::get("/feeds/search", |r| r.to(handler::feed::search)),
+        Endpoint::get("/feed/{id}", |r| r.to(handler::feed::by_name_or_id)),
+        Endpoint::get("/feed/{id}/related", |r| r.to(handler::feed::related)),
+        Endpoint::post("/feed/preview", |r| {
+            r.to(save_preview_feed::preview::create)
+        })
+        .login(),
+        Endpoint::post("/feed/new", |r| r.to(save_preview_feed::save::save)).login(),
+        Endpoint::patch("/feed/update", |r| r.to(user::update_feed)).login(),
+        Endpoint::get("/episode/{id}", |r| r.to(handler::episode::by_episode_id)),
+        Endpoint::get("/episodes/search", |r| r.to(handler::episode::search)),
+        Endpoint::get("/episodes/{feed_id}", |r| {
+            r.to(handler::episode::by_feed_id)
+        }),
+        Endpoint::get("/img-path/{feed_title}", |r| r.to(handler::image_for_feed)),
+        Endpoint::post("/subscription", |r| r.to(handler::subscription::subscribe)).login(),
+        Endpoint::delete("/subscription", |r| {
+            r.to(handler::subscription::unsubscribe)
+        })
+        .login(),
+        Endpoint::get("/subscription/user", |r| r.to(user::subscriptions)).login(),
+        Endpoint::post("/subscription/user", |r| {
+            r.to(handler::subscription::subscription_info)
+        })
+        .login(),
+        Endpoint::get("/categories", |r| r.to(handler::category::all)),
+        Endpoint::get("/category/{category}", |r| {
+            r.to(handler::category::by_id_or_name)
+        }),
+        Endpoint::get("/category/{category}/feeds", |r| {
+            r.to(handler::feed::by_category)
+        }),
+        Endpoint::get("/tags", |r| r.to(handler::tag::all)),
+        Endpoint::get("/tag/{name}/episodes", |r| r.to(handler::tag::episodes)),
+        Endpoint::get("/tag/{name}/feeds", |r| r.to(handler::tag::feeds)),
+        Endpoint::get("/authors", |r| r.to(handler::author::all)),
+        Endpoint::get("/author/{author_id_name}/feeds", |r| {
+            r.to(handler::author::feeds)
+        }),
+        Endpoint::post("/comment", |r| r.to(handler::comment::new)).login(),
+        Endpoint::get("/comments/{id}", |r| r.to(handler::comment::get_for_feed)),
+        // auth
+        Endpoint::post("/auth/register", |r| r.to(auth::register)),
+        Endpoint::post("/auth/login", |r| r.to(auth::login)),
+        Endpoint::post("/auth/logout", |r| r.to(auth::logout)).login(),
+        // user
+        Endpoint::get("/user/info", |r| r.to(auth::user_info)).login(),
+        Endpoint::get("/user/feeds", |r| r.to(user::submitted_feeds)).login(),
+        Endpoint::get("/user/job/{id}", |r| r.to(handler::job::status)).login(),
+        Endpoint::get("/user/socket/jobs", |r| r.to(handler::job::register_socket)).login(),
+        // moderator
+        Endpoint::get("/admin/review/unassigned", |r| {
+            r.to(handler::manage::all_unassigned)
+        })
+        .moderator(),
+        Endpoint::get("/admin/review/inbox", |r| {
+            r.to(handler::manage::reviewer_inbox)
+        })
+        .moderator(),
+        Endpoint::patch("/admin/review/assign", |r| {
+            r.to(handler::manage::assign_for_review)
+        })
+        .moderator(),
+        Endpoint::get("/admin/review/reviewed", |r| {
+            r.to(handler::manage::reviewed)
+        })
+        .moderator(),
+        Endpoint::patch("/admin/review", |r| r.to(handler::manage::review_feed)).moderator(),
+        Endpoint::get("/admin/cache", |r| r.to(handler::manage::cache_stats)).moderator(),
+        Endpoint::get("/admin/jobs", |r| r.to(handler::manage::jobs)).moderator(),
+        Endpoint::patch("/admin/job/{id}/retry", |r| {
+            r.to(handler::manage::retry_job)
+        })
+        .moderator(),
+        Endpoint::get("/admin/webhooks", |r| r.to(handler::webhook::all)).moderator(),
+        Endpoint::post("/admin/webhooks", |r| r.to(handler::webhook::create)).moderator(),
+        Endpoint::delete("/admin/webhook/{id}", |r| r.to(handler::webhook::delete)).moderator(),
+        Endpoint::get("/admin/webhook/{id}/deliveries", |r| {
+            r.to(handler::webhook::deliveries)
+        })
+        .moderator(),
+        Endpoint::patch("/admin/feed/{id}/artwork", |r| {
+            r.to(handler::manage::refresh_artwork)
+        })
+        .moderator(),
+        Endpoint::get("/admin/socket/unassigned", |r| {
+            r.to(handler::manage::register_socket)
+        })
+        .moderator(),
+        Endpoint::post("/admin/register", |r| {
+            r.to(handler::manage::register_moderator)
+        })
+        .moderator(),
+    ]
 }
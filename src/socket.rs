@@ -4,11 +4,50 @@ use actix_broker::BrokerSubscribe;
 use actix_web_actors::ws;
 use std::time::{Duration, Instant};
 
-use crate::handler::manage::ModeratorFeed;
+use crate::{handler::manage::ModeratorFeed, job::Job};
 
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// Sockets that answer pings and close after `CLIENT_TIMEOUT` without a pong.
+trait HeartBeat: Actor<Context = ws::WebsocketContext<Self>> {
+    fn heart_beat(&mut self) -> &mut Instant;
+
+    fn heart_beat_start(&self, ctx: &mut <Self as Actor>::Context) {
+        ctx.run_interval(HEARTBEAT_INTERVAL, |act, ctx| {
+            // check client heartbeats
+            if Instant::now().duration_since(*act.heart_beat()) > CLIENT_TIMEOUT {
+                ctx.stop();
+
+                return;
+            }
+            ctx.ping(b"");
+        });
+    }
+
+    fn handle_frame(
+        &mut self,
+        msg: Result<ws::Message, ws::ProtocolError>,
+        ctx: &mut <Self as Actor>::Context,
+    ) {
+        match msg {
+            Ok(ws::Message::Ping(msg)) => ctx.pong(&msg),
+            Ok(ws::Message::Pong(_msg)) => {
+                *self.heart_beat() = Instant::now();
+            }
+            Ok(ws::Message::Close(reason)) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            Err(_err) => {
+                ctx.stop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// New submissions for moderators, fed by [`crate::notify`] with those of every instance.
 pub struct LiveFeedSocket {
     heart_beat: Instant,
 }
@@ -29,35 +68,17 @@ impl LiveFeedSocket {
             heart_beat: Instant::now(),
         }
     }
-    fn heart_beat_start(&self, ctx: &mut <Self as Actor>::Context) {
-        ctx.run_interval(HEARTBEAT_INTERVAL, |act, ctx| {
-            // check client heartbeats
-            if Instant::now().duration_since(act.heart_beat) > CLIENT_TIMEOUT {
-                ctx.stop();
+}
 
-                return;
-            }
-            ctx.ping(b"");
-        });
+impl HeartBeat for LiveFeedSocket {
+    fn heart_beat(&mut self) -> &mut Instant {
+        &mut self.heart_beat
     }
 }
 
 impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for LiveFeedSocket {
     fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
-        match msg {
-            Ok(ws::Message::Ping(msg)) => ctx.pong(&msg),
-            Ok(ws::Message::Pong(_msg)) => {
-                self.heart_beat = Instant::now();
-            }
-            Ok(ws::Message::Close(reason)) => {
-                ctx.close(reason);
-                ctx.stop();
-            }
-            Err(_err) => {
-                ctx.stop();
-            }
-            _ => {}
-        }
+        self.handle_frame(msg, ctx);
     }
 }
 
@@ -82,3 +103,50 @@ impl Message {
         serde_json::to_string(&self.0).unwrap()
     }
 }
+
+/// Progress of the submission jobs of one user.
+pub struct JobSocket {
+    heart_beat: Instant,
+    user_id: i32,
+}
+
+impl JobSocket {
+    pub fn new(user_id: i32) -> Self {
+        Self {
+            heart_beat: Instant::now(),
+            user_id,
+        }
+    }
+}
+
+impl Actor for JobSocket {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.subscribe_system_async::<Job>(ctx);
+        self.heart_beat_start(ctx);
+        ctx.set_mailbox_capacity(24);
+    }
+}
+
+impl HeartBeat for JobSocket {
+    fn heart_beat(&mut self) -> &mut Instant {
+        &mut self.heart_beat
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for JobSocket {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        self.handle_frame(msg, ctx);
+    }
+}
+
+impl Handler<Job> for JobSocket {
+    type Result = ();
+
+    fn handle(&mut self, job: Job, ctx: &mut Self::Context) -> Self::Result {
+        if job.user_id == self.user_id {
+            ctx.text(serde_json::to_string(&job).unwrap());
+        }
+    }
+}
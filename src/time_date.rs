@@ -7,6 +7,11 @@ pub fn parse_datetime_rfc822(stamp: &str) -> Result<DateTime<Utc>, chrono::Parse
     DateTime::parse_from_rfc2822(stamp).map(|t| t.into())
 }
 
+/// IMF-fixdate as used by `Last-Modified`, parsed again by `parse_datetime_rfc822`.
+pub fn format_http_date(date: &DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
 fn digit_thing(s: &str) -> Option<i64> {
     match s.len() {
         2 | 3 => match s {
@@ -99,6 +104,12 @@ mod test {
             parse_datetime_rfc822(time).expect(time);
         }
     }
+    #[test]
+    fn test_http_date() {
+        let date = parse_datetime_rfc822("Mon, 23 Nov 2020 22:07:46 GMT").unwrap();
+        assert_eq!(format_http_date(&date), "Mon, 23 Nov 2020 22:07:46 GMT");
+    }
+
     //The duration should be in one of the following formats: HH:MM:SS, H:MM:SS, MM:SS, M:SS and MMM::SS
     #[test]
     fn test_duration() {
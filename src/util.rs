@@ -36,6 +36,30 @@ pub fn percent_decode(text: &str) -> String {
     percent_decode_str(text).decode_utf8_lossy().to_string()
 }
 
+/// `text` as a literal inside a `LIKE` pattern, with its wildcards escaped by `\`.
+pub fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 pub fn serialize<D>(data: D) -> Result<actix_web::web::Json<D>, ApiError> {
     Ok(Json(data))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("news"), "news");
+        assert_eq!(escape_like("100%_sure"), "100\\%\\_sure");
+        assert_eq!(escape_like("a\\b"), "a\\\\b");
+    }
+}
@@ -0,0 +1,230 @@
+//! Outgoing webhooks for catalogue events.
+//!
+//! Every event is logged as one `webhook_delivery` row per subscribed webhook and sent
+//! by the job queue, so failed deliveries are retried with its backoff.
+//! The body is signed with the secret of the webhook, receivers compare
+//! `X-Elenco-Signature` against `sha256=` and the hex HMAC-SHA256 of the raw body.
+use hmac::{Hmac, Mac, NewMac};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Serialize;
+use sha2::Sha256;
+use std::time::Duration;
+
+use crate::{
+    db,
+    model::webhook::{DeliveryStatus, Event},
+    queue::{self, Task},
+    Client, State,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const SECRET_LENGTH: usize = 32;
+
+pub fn generate_secret() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SECRET_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// Hex encoded HMAC-SHA256 of `body`.
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any length");
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Logs a delivery of `event` for every subscribed webhook and queues them.
+/// Failures are only logged, the change that caused the event has already happened.
+pub async fn emit<T: Serialize>(client: &Client, event: Event, data: &T) {
+    let payload = serde_json::json!({
+        "event": event.as_str(),
+        "occurred": chrono::Utc::now().to_rfc3339(),
+        "data": data,
+    });
+    let deliveries = match db::webhook::insert_deliveries(client, event.as_str(), &payload).await {
+        Ok(deliveries) => deliveries,
+        Err(err) => {
+            log::error!("could not log {} deliveries: {}", event.as_str(), err);
+            return;
+        }
+    };
+    for delivery_id in deliveries {
+        if let Err(err) = queue::enqueue(client, &Task::DeliverWebhook { delivery_id }).await {
+            log::error!("could not queue webhook delivery {}: {}", delivery_id, err);
+        }
+    }
+}
+
+/// Sends a logged delivery and records the outcome,
+/// it is only marked `failed` once `last_attempt` failed as well.
+pub async fn deliver(
+    state: &State,
+    delivery_id: i64,
+    last_attempt: bool,
+) -> Result<(), anyhow::Error> {
+    let client = state.db_pool.get().await?;
+    let delivery = match db::webhook::delivery(&client, delivery_id).await? {
+        Some(delivery) => delivery,
+        // the webhook was deleted in the meantime
+        None => return Ok(()),
+    };
+    drop(client);
+    let body = serde_json::to_vec(&delivery.payload)?;
+    let result = post(
+        &delivery.url,
+        &delivery.secret,
+        &delivery.event,
+        delivery.id,
+        body,
+    )
+    .await;
+
+    let client = state.db_pool.get().await?;
+    let failed = if last_attempt {
+        DeliveryStatus::Failed
+    } else {
+        DeliveryStatus::Pending
+    };
+    match result {
+        Ok(status) if status.is_success() => {
+            db::webhook::update_delivery(
+                &client,
+                delivery_id,
+                DeliveryStatus::Delivered,
+                Some(status.as_u16().into()),
+                None,
+            )
+            .await?;
+            Ok(())
+        }
+        Ok(status) => {
+            let error = format!("receiver answered {}", status);
+            db::webhook::update_delivery(
+                &client,
+                delivery_id,
+                failed,
+                Some(status.as_u16().into()),
+                Some(&error),
+            )
+            .await?;
+            Err(anyhow::anyhow!(error))
+        }
+        Err(err) => {
+            let error = err.to_string();
+            db::webhook::update_delivery(&client, delivery_id, failed, None, Some(&error)).await?;
+            Err(err.into())
+        }
+    }
+}
+
+async fn post(
+    url: &str,
+    secret: &str,
+    event: &str,
+    delivery_id: i64,
+    body: Vec<u8>,
+) -> Result<reqwest::StatusCode, reqwest::Error> {
+    let response = reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        .build()?
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Elenco-Event", event)
+        .header("X-Elenco-Delivery", delivery_id.to_string())
+        .header(
+            "X-Elenco-Signature",
+            format!("sha256={}", signature(secret, &body)),
+        )
+        .body(body)
+        .send()
+        .await?;
+    Ok(response.status())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    /// Answers a single request with `status` and hands back its headers and body.
+    fn receiver(status: u16) -> (String, thread::JoinHandle<(Vec<String>, Vec<u8>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end().to_string();
+                if line.is_empty() {
+                    break;
+                }
+                headers.push(line.to_lowercase());
+            }
+            let length = headers
+                .iter()
+                .find_map(|h| h.strip_prefix("content-length: "))
+                .and_then(|length| length.parse().ok())
+                .unwrap_or(0);
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                status
+            )
+            .unwrap();
+            (headers, body)
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_signature() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_is_signed() {
+        let (url, receiver) = receiver(204);
+        let body = br#"{"event":"feed.submitted","data":{"id":1}}"#.to_vec();
+        let status = post(&url, "secret", "feed.submitted", 42, body.clone())
+            .await
+            .unwrap();
+        assert_eq!(status.as_u16(), 204);
+
+        let (headers, received) = receiver.join().unwrap();
+        assert_eq!(received, body);
+        assert!(headers.contains(&"x-elenco-event: feed.submitted".to_string()));
+        assert!(headers.contains(&"x-elenco-delivery: 42".to_string()));
+        let expected = format!("x-elenco-signature: sha256={}", signature("secret", &body));
+        assert!(headers.contains(&expected));
+    }
+
+    #[tokio::test]
+    async fn test_post_reports_failed_status() {
+        let (url, receiver) = receiver(500);
+        let status = post(&url, "secret", "comment.created", 1, b"{}".to_vec())
+            .await
+            .unwrap();
+        assert!(!status.is_success());
+        receiver.join().unwrap();
+    }
+}